@@ -0,0 +1,15 @@
+mod advisor;
+mod coverage;
+mod grit;
+mod inference;
+mod ko;
+mod matchup;
+mod speed;
+
+pub use advisor::*;
+pub use coverage::*;
+pub use grit::*;
+pub use inference::*;
+pub use ko::*;
+pub use matchup::*;
+pub use speed::*;
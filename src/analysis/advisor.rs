@@ -0,0 +1,202 @@
+use crate::{analysis, battle, data};
+
+use data::{BattlePokemon, Move, MoveEffect, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StyleObjective
+{
+	/// The most damage from this one attack
+	Damage,
+	/// The most damage for the action time the attack costs
+	DamageRate,
+	Accuracy,
+	Crit,
+	/// The best chance of landing the move's status effect
+	Effect,
+	/// Gaining as much ground on the target's next turn as possible
+	Tempo,
+}
+impl std::str::FromStr for StyleObjective
+{
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err>
+	{
+		match s.to_lowercase().as_str()
+		{
+			"damage" => Ok(Self::Damage),
+			"rate" | "damage-rate" => Ok(Self::DamageRate),
+			"accuracy" => Ok(Self::Accuracy),
+			"crit" => Ok(Self::Crit),
+			"effect" => Ok(Self::Effect),
+			"tempo" => Ok(Self::Tempo),
+			_ => Err(()),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct StyleOption
+{
+	pub style: Style,
+	pub min_damage: i32,
+	pub max_damage: i32,
+	/// Average damage once accuracy, crits and rolls are all accounted for
+	pub expected_damage: f64,
+	pub hit_chance: f64,
+	pub crit_chance: f64,
+	/// The best chance of one of the move's statuses being applied when it hits, if it has any
+	pub effect_chance: Option<f64>,
+	/// How much action time using the move costs the attacker relative to the target
+	pub action_time_cost: i32,
+	/// How far ahead of the target the attacker is afterwards, where a positive lead means the
+	/// attacker acts again before the target does
+	pub turn_lead: i32,
+}
+impl StyleOption
+{
+	pub fn acts_again(&self) -> bool
+	{
+		self.turn_lead >= 0
+	}
+
+	pub fn damage_rate(&self) -> f64
+	{
+		self.expected_damage / f64::from(self.action_time_cost.max(1))
+	}
+
+	fn score(&self, objective: StyleObjective) -> f64
+	{
+		match objective
+		{
+			StyleObjective::Damage => self.expected_damage,
+			StyleObjective::DamageRate => self.damage_rate(),
+			StyleObjective::Accuracy => self.hit_chance,
+			StyleObjective::Crit => self.crit_chance,
+			StyleObjective::Effect => self.effect_chance.unwrap_or_default() * self.hit_chance,
+			StyleObjective::Tempo => f64::from(self.turn_lead),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct StyleAdvice
+{
+	/// One option per style, in `Style::ALL` order
+	pub options: Vec<StyleOption>,
+	pub recommended: Style,
+}
+
+/// Compares every style of `mv` used by `attacker` on `target` and picks the best one for
+/// `objective`. Ties are broken by expected damage, then by the order of `Style::ALL`
+pub fn advise_style(
+	attacker: &BattlePokemon,
+	target: &BattlePokemon,
+	mv: &Move,
+	objective: StyleObjective,
+) -> StyleAdvice
+{
+	let options = Style::ALL
+		.into_iter()
+		.map(|style| style_option(attacker, target, mv, style))
+		.collect::<Vec<_>>();
+
+	let mut best = &options[0];
+	for option in &options[1..]
+	{
+		let (score, best_score) = (option.score(objective), best.score(objective));
+		if score > best_score
+			|| ((score - best_score).abs() < f64::EPSILON
+				&& option.expected_damage > best.expected_damage)
+		{
+			best = option;
+		}
+	}
+
+	StyleAdvice {
+		recommended: best.style,
+		options,
+	}
+}
+
+fn style_option(attacker: &BattlePokemon, target: &BattlePokemon, mv: &Move, style: Style)
+	-> StyleOption
+{
+	let mv = &battle::resolve_move(attacker, target, mv);
+	let base_damage = BattlePokemon::calculate_damage_no_roll(
+		attacker,
+		target,
+		&mv.power,
+		mv.category,
+		mv.move_type,
+		style,
+	);
+	let hit_chance = analysis::hit_chance(target, mv, style);
+	let crit_chance = analysis::crit_chance(attacker, mv, style);
+	let expected_damage =
+		analysis::hit_outcomes(base_damage, hit_chance, crit_chance, attacker.rules)
+		.iter()
+		.map(|(damage, chance)| f64::from(*damage) * chance)
+		.sum();
+
+	let effect_chance = mv
+		.effects
+		.iter()
+		.filter_map(|eff| {
+			if let MoveEffect::ApplyStatus { chance, .. } = eff
+			{
+				Some(f64::from(chance[style].clamp(0, 100)) / 100.0)
+			}
+			else
+			{
+				None
+			}
+		})
+		.reduce(f64::max);
+
+	let action_time_cost = attacker.base_action_time() + mv.user_action_time[style]
+		- mv.target_action_time[style];
+	let turn_lead = (target.action_time() + mv.target_action_time[style])
+		- (attacker.action_time() + attacker.base_action_time() + mv.user_action_time[style]);
+
+	StyleOption {
+		style,
+		min_damage: BattlePokemon::apply_roll(base_damage, attacker.rules.damage_rolls.start),
+		max_damage: BattlePokemon::apply_roll(base_damage, attacker.rules.damage_rolls.end - 1),
+		expected_damage,
+		hit_chance,
+		crit_chance,
+		effect_chance,
+		action_time_cost,
+		turn_lead,
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{data::Pokemon, MOVE_MAP, RULESET, SPECIES_MAP};
+
+	#[test]
+	fn advises_on_the_move_as_it_resolves()
+	{
+		let dialga = Pokemon::new(&SPECIES_MAP["dialga-origin"]).set_level(70);
+		let garchomp = Pokemon::new(&SPECIES_MAP["garchomp"]).set_level(70);
+		let attacker = BattlePokemon::new(&dialga, &RULESET);
+		let target = BattlePokemon::new(&garchomp, &RULESET);
+		let roar_of_time = &MOVE_MAP["roar_of_time"];
+
+		// origin forme dialga's roar of time hits harder but less accurately, and takes longer
+		let advice = advise_style(&attacker, &target, roar_of_time, StyleObjective::Accuracy);
+		assert_eq!(advice.recommended, Style::Strong);
+		let hit_chances = advice.options.iter().map(|it| it.hit_chance).collect::<Vec<_>>();
+		assert_eq!(hit_chances, [0.75, 0.75, 0.85]);
+		let base_action_time = attacker.base_action_time();
+		let costs = advice.options.iter().map(|it| it.action_time_cost).collect::<Vec<_>>();
+		assert_eq!(costs, [6, 1, 10].map(|it| base_action_time + it));
+
+		let advice = advise_style(&attacker, &target, roar_of_time, StyleObjective::Tempo);
+		assert_eq!(advice.recommended, Style::Agile);
+	}
+}
@@ -0,0 +1,182 @@
+use crate::data;
+
+use data::{RegMap, Team, Type, TypeChart};
+
+#[derive(Debug, Clone)]
+pub struct DefensiveRow<'t>
+{
+	pub attacking_type: &'t Type,
+	/// The multiplier the attacking type deals to each member, in team order
+	pub multipliers: Vec<f64>,
+}
+impl DefensiveRow<'_>
+{
+	pub fn weak_count(&self) -> usize
+	{
+		self.multipliers.iter().filter(|it| **it > 1.0).count()
+	}
+
+	pub fn resist_count(&self) -> usize
+	{
+		self.multipliers.iter().filter(|it| **it < 1.0).count()
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct OffensiveRow<'t>
+{
+	pub defending_type: &'t Type,
+	pub best_multiplier: f64,
+	/// Every damaging move on the team that is super effective against this type, as the index
+	/// of the member that knows it along with the move's id
+	pub super_effective_moves: Vec<(usize, Box<str>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoverageReport<'t>
+{
+	pub defensive: Vec<DefensiveRow<'t>>,
+	pub offensive: Vec<OffensiveRow<'t>>,
+}
+impl<'t> CoverageReport<'t>
+{
+	/// Attacking types that at least `min_members` members are weak to
+	pub fn shared_weaknesses(&self, min_members: usize) -> Vec<&'t Type>
+	{
+		self.defensive
+			.iter()
+			.filter(|it| it.weak_count() >= min_members)
+			.map(|it| it.attacking_type)
+			.collect()
+	}
+
+	/// Types that none of the team's damaging moves are super effective against
+	pub fn uncovered(&self) -> Vec<&'t Type>
+	{
+		self.offensive
+			.iter()
+			.filter(|it| it.super_effective_moves.is_empty())
+			.map(|it| it.defending_type)
+			.collect()
+	}
+}
+
+pub fn analyze_coverage<'t>(
+	team: &Team,
+	type_map: &'t RegMap<Type>,
+	type_chart: &TypeChart,
+) -> CoverageReport<'t>
+{
+	let mut types = type_map.values().collect::<Vec<_>>();
+	types.sort_by(|a, b| a.id.cmp(&b.id));
+
+	let defensive = types
+		.iter()
+		.map(|typ| DefensiveRow {
+			attacking_type: typ,
+			multipliers: team
+				.members
+				.iter()
+				.map(|it| it.species.types.damage_multiplier_from(typ))
+				.collect(),
+		})
+		.collect();
+
+	let offensive = types
+		.iter()
+		.map(|typ| {
+			let damaging_moves = team
+				.members
+				.iter()
+				.enumerate()
+				.flat_map(|(i, member)| {
+					member
+						.moves()
+						.filter(|mv| mv.category.is_damaging())
+						.map(move |mv| (i, mv))
+				})
+				.map(|(i, mv)| (i, mv, type_chart.multiplier(mv.move_type, &[*typ])))
+				.collect::<Vec<_>>();
+
+			let mut super_effective_moves = damaging_moves
+				.iter()
+				.filter(|it| it.2 > 1.0)
+				.map(|(i, mv, _)| (*i, mv.id.clone()))
+				.collect::<Vec<_>>();
+			super_effective_moves.sort();
+
+			OffensiveRow {
+				defending_type: typ,
+				best_multiplier: damaging_moves.iter().map(|it| it.2).fold(0.0, f64::max),
+				super_effective_moves,
+			}
+		})
+		.collect();
+
+	CoverageReport {
+		defensive,
+		offensive,
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{data::Pokemon, MOVE_MAP, SPECIES_MAP, TYPE_CHART, TYPE_MAP};
+
+	fn ids(types: &[&Type]) -> Vec<String>
+	{
+		types.iter().map(|it| it.id.to_string()).collect()
+	}
+
+	#[test]
+	fn finds_the_weaknesses_and_gaps_of_a_team()
+	{
+		let team = Team::new(String::from("Coverage"))
+			.add_member(
+				Pokemon::new(&SPECIES_MAP["garchomp"])
+					.add_moves(["dragon_claw", "stone_edge"].map(|it| &MOVE_MAP[it])),
+			)
+			.add_member(
+				Pokemon::new(&SPECIES_MAP["gengar"])
+					.add_moves(["shadow_ball", "sludge_bomb"].map(|it| &MOVE_MAP[it])),
+			);
+		let report = analyze_coverage(&team, &TYPE_MAP, &TYPE_CHART);
+
+		let defensive = |id: &str| {
+			let row = report
+				.defensive
+				.iter()
+				.find(|it| &*it.attacking_type.id == id)
+				.unwrap();
+			row.multipliers.clone()
+		};
+		assert_eq!(defensive("ice"), [2.5, 1.0]);
+		assert_eq!(defensive("electric"), [0.0, 1.0]);
+		assert_eq!(defensive("ground"), [1.0, 2.0]);
+		assert_eq!(defensive("fairy"), [2.0, 0.5]);
+		assert!(report.shared_weaknesses(2).is_empty());
+		assert_eq!(
+			ids(&report.shared_weaknesses(1)),
+			["dark", "dragon", "fairy", "ghost", "ground", "ice", "psychic"]
+		);
+
+		let offensive = |id: &str| {
+			report
+				.offensive
+				.iter()
+				.find(|it| &*it.defending_type.id == id)
+				.unwrap()
+		};
+		assert_eq!(offensive("fire").super_effective_moves, [(0, Box::from("stone_edge"))]);
+		assert_eq!(offensive("fairy").super_effective_moves, [(1, Box::from("sludge_bomb"))]);
+		assert!(offensive("steel").super_effective_moves.is_empty());
+		assert!((offensive("dragon").best_multiplier - 2.0).abs() < 1e-9);
+		assert!((offensive("steel").best_multiplier - 1.0).abs() < 1e-9);
+		assert_eq!(
+			ids(&report.uncovered()),
+			["dark", "electric", "fighting", "ground", "normal", "poison", "rock", "steel", "water"]
+		);
+	}
+}
@@ -0,0 +1,413 @@
+use crate::data;
+
+use data::{Pokemon, Stat, StatBlock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GritItem
+{
+	Dust,
+	Gravel,
+	Pebble,
+	Rock,
+}
+impl GritItem
+{
+	pub const ALL: [Self; 4] = [Self::Dust, Self::Gravel, Self::Pebble, Self::Rock];
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GritCost
+{
+	pub value: u32,
+	pub costs: Box<[u32]>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GritTable
+{
+	pub dust: GritCost,
+	pub gravel: GritCost,
+	pub pebble: GritCost,
+	pub rock: GritCost,
+}
+impl std::ops::Index<GritItem> for GritTable
+{
+	type Output = GritCost;
+	fn index(&self, index: GritItem) -> &Self::Output
+	{
+		match index
+		{
+			GritItem::Dust => &self.dust,
+			GritItem::Gravel => &self.gravel,
+			GritItem::Pebble => &self.pebble,
+			GritItem::Rock => &self.rock,
+		}
+	}
+}
+impl GritTable
+{
+	/// How many of `item` it takes to raise an effort level from `level`, if it can at all
+	pub fn cost(&self, item: GritItem, level: i32) -> Option<u32>
+	{
+		usize::try_from(level)
+			.ok()
+			.and_then(|level| self[item].costs.get(level))
+			.copied()
+	}
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GritCounts
+{
+	pub dust: u32,
+	pub gravel: u32,
+	pub pebble: u32,
+	pub rock: u32,
+}
+impl std::ops::Index<GritItem> for GritCounts
+{
+	type Output = u32;
+	fn index(&self, index: GritItem) -> &Self::Output
+	{
+		match index
+		{
+			GritItem::Dust => &self.dust,
+			GritItem::Gravel => &self.gravel,
+			GritItem::Pebble => &self.pebble,
+			GritItem::Rock => &self.rock,
+		}
+	}
+}
+impl std::ops::IndexMut<GritItem> for GritCounts
+{
+	fn index_mut(&mut self, index: GritItem) -> &mut Self::Output
+	{
+		match index
+		{
+			GritItem::Dust => &mut self.dust,
+			GritItem::Gravel => &mut self.gravel,
+			GritItem::Pebble => &mut self.pebble,
+			GritItem::Rock => &mut self.rock,
+		}
+	}
+}
+impl std::ops::Add for GritCounts
+{
+	type Output = Self;
+	fn add(self, rhs: Self) -> Self::Output
+	{
+		Self {
+			dust: self.dust + rhs.dust,
+			gravel: self.gravel + rhs.gravel,
+			pebble: self.pebble + rhs.pebble,
+			rock: self.rock + rhs.rock,
+		}
+	}
+}
+impl GritCounts
+{
+	pub fn value(&self, table: &GritTable) -> u32
+	{
+		GritItem::ALL
+			.iter()
+			.map(|it| self[*it] * table[*it].value)
+			.sum()
+	}
+
+	pub fn fits_in(&self, inventory: &Self) -> bool
+	{
+		GritItem::ALL.iter().all(|it| self[*it] <= inventory[*it])
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct StatPlan
+{
+	pub stat: Stat,
+	pub from: i32,
+	pub to: i32,
+	pub items: GritCounts,
+	pub value: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct GritPlan
+{
+	pub stats: Vec<StatPlan>,
+	pub total: GritCounts,
+	pub total_value: u32,
+}
+
+pub fn plan_grit_for(
+	pokemon: &Pokemon,
+	target: &StatBlock,
+	inventory: &GritCounts,
+	table: &GritTable,
+) -> Result<GritPlan, GritPlanError>
+{
+	plan_grit(&pokemon.effort_levels, target, inventory, table)
+}
+
+/// Finds the cheapest way to raise `current` to `target` using only items from `inventory`
+pub fn plan_grit(
+	current: &StatBlock,
+	target: &StatBlock,
+	inventory: &GritCounts,
+	table: &GritTable,
+) -> Result<GritPlan, GritPlanError>
+{
+	let mut steps = Vec::new();
+	for stat in Stat::ALL
+	{
+		if !(0..=10).contains(&current[stat]) || !(0..=10).contains(&target[stat])
+		{
+			return Err(GritPlanError::OutOfRange(stat));
+		}
+		if target[stat] < current[stat]
+		{
+			return Err(GritPlanError::CannotLower(stat));
+		}
+		steps.extend((current[stat]..target[stat]).map(|level| (stat, level)));
+	}
+	// a stat's steps cost the same as any other's from the same level, so going through them by
+	// level keeps the ways of paying that are worth comparing side by side
+	steps.sort_by_key(|(_, level)| *level);
+
+	let (needed, cheapest) = remaining_needs(&steps, table);
+	let bound = greedy_value(&steps, inventory, table).unwrap_or(u32::MAX);
+
+	// the ways of paying for each step so far worth carrying on with. a way is dropped if it
+	// can't finish any cheaper than the greedy plan, or if a cheaper one leaves at least as many
+	// of every item the rest of the steps could run short of. with plenty of items that leaves
+	// just the cheapest way
+	let mut nodes: Vec<(Option<usize>, Option<GritItem>)> = vec![(None, None)];
+	let mut layer = vec![(GritCounts::default(), 0)];
+	for (index, (_, level)) in steps.iter().enumerate()
+	{
+		let mut next = Vec::new();
+		for (used, node) in &layer
+		{
+			for item in GritItem::ALL
+			{
+				let Some(cost) = table.cost(item, *level)
+				else
+				{
+					continue;
+				};
+
+				let mut used = *used;
+				used[item] += cost;
+				if used.fits_in(inventory)
+					&& used.value(table).saturating_add(cheapest[index + 1]) <= bound
+				{
+					next.push((used, *node, item));
+				}
+			}
+		}
+		next.sort_by_key(|(used, ..)| (used.value(table), GritItem::ALL.map(|it| used[it])));
+
+		let mut kept: Vec<(GritCounts, usize, GritCounts)> = Vec::new();
+		for (used, parent, item) in next
+		{
+			let mut left = GritCounts::default();
+			for item in GritItem::ALL
+			{
+				left[item] = (inventory[item] - used[item]).min(needed[index + 1][item]);
+			}
+			if !kept.iter().any(|(.., other)| left.fits_in(other))
+			{
+				nodes.push((Some(parent), Some(item)));
+				kept.push((used, nodes.len() - 1, left));
+			}
+		}
+		layer = kept.into_iter().map(|(used, node, _)| (used, node)).collect();
+	}
+
+	let (total, mut node) = layer
+		.into_iter()
+		.next()
+		.ok_or(GritPlanError::NotEnoughItems)?;
+
+	let mut items_per_step = Vec::with_capacity(steps.len());
+	while let (Some(parent), Some(item)) = nodes[node]
+	{
+		items_per_step.push(item);
+		node = parent;
+	}
+	items_per_step.reverse();
+
+	let mut stats = Stat::ALL
+		.iter()
+		.filter(|stat| target[**stat] > current[**stat])
+		.map(|stat| StatPlan {
+			stat: *stat,
+			from: current[*stat],
+			to: target[*stat],
+			items: GritCounts::default(),
+			value: 0,
+		})
+		.collect::<Vec<_>>();
+	for ((stat, level), item) in steps.iter().zip(items_per_step)
+	{
+		let cost = table.cost(item, *level).unwrap_or_default();
+		if let Some(plan) = stats.iter_mut().find(|it| it.stat == *stat)
+		{
+			plan.items[item] += cost;
+			plan.value += cost * table[item].value;
+		}
+	}
+
+	Ok(GritPlan {
+		total_value: total.value(table),
+		stats,
+		total,
+	})
+}
+
+/// How many of each item the steps from each one onwards could possibly need, and the least they
+/// could cost
+fn remaining_needs(steps: &[(Stat, i32)], table: &GritTable) -> (Vec<GritCounts>, Vec<u32>)
+{
+	let mut needed = vec![GritCounts::default(); steps.len() + 1];
+	let mut cheapest = vec![0_u32; steps.len() + 1];
+	for (index, (_, level)) in steps.iter().enumerate().rev()
+	{
+		needed[index] = needed[index + 1];
+		let mut cheapest_item = u32::MAX;
+		for item in GritItem::ALL
+		{
+			if let Some(cost) = table.cost(item, *level)
+			{
+				needed[index][item] += cost;
+				cheapest_item = cheapest_item.min(cost * table[item].value);
+			}
+		}
+		cheapest[index] = cheapest[index + 1].saturating_add(cheapest_item);
+	}
+	(needed, cheapest)
+}
+
+/// What paying for each step with the cheapest item left comes to, if that gets all the way
+fn greedy_value(steps: &[(Stat, i32)], inventory: &GritCounts, table: &GritTable) -> Option<u32>
+{
+	let mut used = GritCounts::default();
+	for (_, level) in steps
+	{
+		let (item, cost) = GritItem::ALL
+			.into_iter()
+			.filter_map(|item| table.cost(item, *level).map(|cost| (item, cost)))
+			.filter(|(item, cost)| used[*item] + cost <= inventory[*item])
+			.min_by_key(|(item, cost)| cost * table[*item].value)?;
+		used[item] += cost;
+	}
+	Some(used.value(table))
+}
+
+#[derive(Debug)]
+pub enum GritPlanError
+{
+	OutOfRange(Stat),
+	CannotLower(Stat),
+	NotEnoughItems,
+}
+impl std::fmt::Display for GritPlanError
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			Self::OutOfRange(stat) => write!(f, "{stat:?} effort level is not in range [0, 10]"),
+			Self::CannotLower(stat) => write!(f, "{stat:?} effort level can't be lowered"),
+			Self::NotEnoughItems => write!(f, "not enough grit items to reach the target"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::GRIT_TABLE;
+
+	const PLENTY: GritCounts = GritCounts {
+		dust: u32::MAX,
+		gravel: u32::MAX,
+		pebble: u32::MAX,
+		rock: u32::MAX,
+	};
+
+	fn attack(level: i32) -> StatBlock
+	{
+		StatBlock {
+			atk: level,
+			..StatBlock::all(0)
+		}
+	}
+
+	#[test]
+	fn plans_each_level_with_its_cheapest_item()
+	{
+		let plan =
+			plan_grit(&StatBlock::all(0), &StatBlock::all(10), &PLENTY, &GRIT_TABLE).unwrap();
+		let expected = GritCounts {
+			dust: 18,
+			rock: 1,
+			..GritCounts::default()
+		};
+
+		assert_eq!(plan.stats.len(), 6);
+		for stat in &plan.stats
+		{
+			assert_eq!((stat.from, stat.to, stat.items, stat.value), (0, 10, expected, 38));
+		}
+		assert_eq!(plan.total, expected + expected + expected + expected + expected + expected);
+		assert_eq!(plan.total_value, 6 * 38);
+	}
+
+	#[test]
+	fn works_around_running_out_of_items()
+	{
+		let inventory = GritCounts {
+			dust: 10,
+			gravel: 2,
+			..PLENTY
+		};
+		let plan = plan_grit(&StatBlock::all(0), &attack(10), &inventory, &GRIT_TABLE).unwrap();
+		assert_eq!(
+			plan.total,
+			GritCounts {
+				dust: 10,
+				gravel: 1,
+				pebble: 2,
+				rock: 1,
+			}
+		);
+		assert_eq!(plan.total_value, 49);
+
+		let inventory = GritCounts {
+			rock: 0,
+			..PLENTY
+		};
+		assert!(matches!(
+			plan_grit(&StatBlock::all(0), &attack(10), &inventory, &GRIT_TABLE),
+			Err(GritPlanError::NotEnoughItems)
+		));
+	}
+
+	#[test]
+	fn rejects_levels_it_cannot_plan_for()
+	{
+		assert!(matches!(
+			plan_grit(&attack(5), &attack(3), &PLENTY, &GRIT_TABLE),
+			Err(GritPlanError::CannotLower(Stat::Atk))
+		));
+		assert!(matches!(
+			plan_grit(&StatBlock::all(0), &attack(11), &PLENTY, &GRIT_TABLE),
+			Err(GritPlanError::OutOfRange(Stat::Atk))
+		));
+		assert!(matches!(
+			plan_grit(&attack(-1), &attack(0), &PLENTY, &GRIT_TABLE),
+			Err(GritPlanError::OutOfRange(Stat::Atk))
+		));
+	}
+}
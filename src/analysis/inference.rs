@@ -0,0 +1,161 @@
+use crate::data;
+
+use data::{
+	Nature, Pokemon, RegMap, Species, Stat, StatBlock, ALPHA_GUARANTEED_STATS,
+	ALPHA_MIN_EFFORT_LEVEL,
+};
+
+/// One nature that could explain a set of observed stats, along with every effort level each stat
+/// could have under it
+#[derive(Debug, Clone)]
+pub struct InferredSpread
+{
+	pub nature_name: Box<str>,
+	pub nature: Nature,
+	pub effort_levels: [Vec<i32>; 6],
+	/// Whether the pokemon is an alpha, which rules out combinations without enough high effort
+	/// levels
+	pub is_alpha: bool,
+}
+impl InferredSpread
+{
+	pub fn options_for(&self, stat: Stat) -> &[i32]
+	{
+		&self.effort_levels[stat_index(stat)]
+	}
+
+	pub fn combination_count(&self) -> usize
+	{
+		if self.is_alpha
+		{
+			self.combinations().count()
+		}
+		else
+		{
+			self.effort_levels.iter().map(Vec::len).product()
+		}
+	}
+
+	/// Every full set of effort levels consistent with the observed stats under this nature
+	pub fn combinations(&self) -> impl Iterator<Item = StatBlock> + '_
+	{
+		let product = self.effort_levels.iter().map(Vec::len).product::<usize>();
+		(0..product)
+			.map(|mut n| {
+				StatBlock::generate(|stat| {
+					let options = self.options_for(stat);
+					let value = options[n % options.len()];
+					n /= options.len();
+					value
+				})
+			})
+			.filter(|it| !self.is_alpha || is_alpha_spread(it))
+	}
+}
+
+/// Works backwards from displayed stats to every nature and effort level combination that could
+/// produce them, sorted by nature name
+pub fn infer_spreads(
+	species: &Species,
+	level: u8,
+	is_alpha: bool,
+	observed: &StatBlock,
+	nature_map: &RegMap<Nature>,
+) -> Vec<InferredSpread>
+{
+	let mut spreads = nature_map
+		.iter()
+		.filter_map(|(name, nature)| {
+			let effort_levels = Stat::ALL.map(|stat| {
+				(0..=10)
+					.filter(|effort_level| {
+						// set after the effort levels, which would otherwise be raised as an
+						// alpha's. that's checked over whole combinations instead
+						let mut candidate = Pokemon::new(species)
+							.set_level(level)
+							.set_nature(*nature)
+							.set_effort_levels(StatBlock::all(*effort_level));
+						candidate.is_alpha = is_alpha;
+						candidate.calculate_stat(stat) == observed[stat]
+					})
+					.collect::<Vec<_>>()
+			});
+
+			let spread = InferredSpread {
+				nature_name: name.clone(),
+				nature: *nature,
+				effort_levels,
+				is_alpha,
+			};
+			let has_alpha_spread = || {
+				let highest = StatBlock::generate(|stat| {
+					spread.options_for(stat).iter().copied().max().unwrap_or_default()
+				});
+				is_alpha_spread(&highest)
+			};
+			(spread.effort_levels.iter().all(|it| !it.is_empty())
+				&& (!is_alpha || has_alpha_spread()))
+			.then_some(spread)
+		})
+		.collect::<Vec<_>>();
+	spreads.sort_by(|a, b| a.nature_name.cmp(&b.nature_name));
+
+	spreads
+}
+
+/// Whether an alpha could have these effort levels, with enough of them at the guaranteed minimum
+fn is_alpha_spread(effort_levels: &StatBlock) -> bool
+{
+	effort_levels
+		.iter()
+		.filter(|it| it.1 >= ALPHA_MIN_EFFORT_LEVEL)
+		.count()
+		>= ALPHA_GUARANTEED_STATS
+}
+
+fn stat_index(stat: Stat) -> usize
+{
+	Stat::ALL
+		.iter()
+		.position(|it| *it == stat)
+		.expect("every stat is in Stat::ALL")
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{NATURE_MAP, SPECIES_MAP};
+
+	fn spreads_for(effort_levels: StatBlock, is_alpha: bool) -> Vec<InferredSpread>
+	{
+		let species = &SPECIES_MAP["garchomp"];
+		let pokemon = Pokemon::new(species)
+			.set_level(50)
+			.set_nature(NATURE_MAP["jolly"])
+			.set_effort_levels(effort_levels);
+		infer_spreads(species, 50, is_alpha, &pokemon.stats(), &NATURE_MAP)
+	}
+
+	#[test]
+	fn finds_the_spread_that_made_the_stats()
+	{
+		let spreads = spreads_for(StatBlock::all(0), false);
+		let jolly = spreads.iter().find(|it| &*it.nature_name == "jolly").unwrap();
+
+		assert!(jolly.combinations().any(|it| it.iter().all(|(_, level)| level == 0)));
+	}
+
+	#[test]
+	fn rules_out_spreads_an_alpha_cannot_have()
+	{
+		assert!(spreads_for(StatBlock::all(0), true).is_empty());
+
+		let spreads = spreads_for(StatBlock::all(5), true);
+		let jolly = spreads.iter().find(|it| &*it.nature_name == "jolly").unwrap();
+		assert!(jolly.is_alpha);
+		assert!(jolly.combination_count() > 0);
+		assert!(jolly.combinations().all(|it| is_alpha_spread(&it)));
+		assert!(!jolly.combinations().any(|it| it.iter().all(|(_, level)| level < 3)));
+	}
+}
@@ -0,0 +1,258 @@
+use crate::{battle, data};
+
+use data::{BattlePokemon, Effect, Move, Ruleset, Style};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct KoChances
+{
+	pub style: Style,
+	pub hit_chance: f64,
+	pub crit_chance: f64,
+	/// The chance the target has fainted before the attacker's next action, after each attack.
+	/// The first entry is for one attack, the second for two, and so on
+	pub within: Vec<f64>,
+}
+
+/// The exact chances of `attacker` knocking out `target` by repeatedly using `mv` in `style`.
+///
+/// Every attack rolls for accuracy, crits and damage roll. Between attacks the target takes its
+/// own turns in action time order, taking damage from any status that deals a fraction of its hp
+/// at the end of its turn for as long as that status lasts. Ties in action time go to the
+/// attacker. The target is assumed not to heal or change anything else about the fight, so the
+/// move is resolved against it once, as it is now
+pub fn ko_chances(
+	attacker: &BattlePokemon,
+	target: &BattlePokemon,
+	mv: &Move,
+	style: Style,
+	max_attacks: usize,
+) -> KoChances
+{
+	let mv = &battle::resolve_move(attacker, target, mv);
+	let hit_chance = hit_chance(target, mv, style);
+	let crit_chance = crit_chance(attacker, mv, style);
+
+	let base_damage = BattlePokemon::calculate_damage_no_roll(
+		attacker,
+		target,
+		&mv.power,
+		mv.category,
+		mv.move_type,
+		style,
+	);
+	let outcomes = hit_outcomes(base_damage, hit_chance, crit_chance, attacker.rules);
+	let residuals = residual_damage(target);
+
+	// chance of each amount of remaining hp, where 0 means the target has fainted
+	let mut remaining = BTreeMap::from([(target.current_hp(), 1.0)]);
+	let mut attacker_time = attacker.action_time();
+	let mut target_time = target.action_time();
+	let mut target_turns = 0;
+	let mut attacks = 0;
+	let mut within = Vec::with_capacity(max_attacks);
+
+	while within.len() < max_attacks
+	{
+		if attacker_time <= target_time
+		{
+			// the previous attack is settled once the attacker is about to act again
+			if attacks > 0
+			{
+				within.push(remaining.get(&0).copied().unwrap_or_default());
+				if within.len() == max_attacks
+				{
+					break;
+				}
+			}
+
+			remaining = spread(&remaining, &outcomes);
+			attacks += 1;
+			attacker_time += attacker.base_action_time() + mv.user_action_time[style];
+			target_time += mv.target_action_time[style];
+		}
+		else
+		{
+			target_turns += 1;
+			let damage = residuals
+				.iter()
+				.filter(|it| it.1 >= target_turns)
+				.map(|it| it.0)
+				.sum::<i32>();
+			if damage > 0
+			{
+				remaining = spread(&remaining, &[(damage, 1.0)]);
+			}
+			target_time += target.base_action_time();
+		}
+	}
+
+	KoChances {
+		style,
+		hit_chance,
+		crit_chance,
+		within,
+	}
+}
+
+pub fn ko_chances_by_style(
+	attacker: &BattlePokemon,
+	target: &BattlePokemon,
+	mv: &Move,
+	max_attacks: usize,
+) -> [KoChances; 3]
+{
+	Style::ALL.map(|style| ko_chances(attacker, target, mv, style, max_attacks))
+}
+
+/// Accuracy above 100 never misses, no matter how evasive the target is
+pub fn hit_chance(target: &BattlePokemon, mv: &Move, style: Style) -> f64
+{
+	if mv.accuracy[style] > 100
+	{
+		1.0
+	}
+	else
+	{
+		(f64::from(mv.accuracy[style]) / 100.0 / target.evasion_multiplier()).clamp(0.0, 1.0)
+	}
+}
+
+pub fn crit_chance(attacker: &BattlePokemon, mv: &Move, style: Style) -> f64
+{
+	1.0 / f64::from(attacker.crit_chance_denominator(mv, style))
+}
+
+/// Every amount of damage one attack can deal along with its chance, misses included
+pub fn hit_outcomes(
+	base_damage: i32,
+	hit_chance: f64,
+	crit_chance: f64,
+	rules: &Ruleset,
+) -> Vec<(i32, f64)>
+{
+	let rolls = rules.damage_rolls.clone();
+	let roll_chance = hit_chance / f64::from(rolls.end - rolls.start);
+
+	let mut outcomes = BTreeMap::from([(0, 1.0 - hit_chance)]);
+	for roll in rolls
+	{
+		let damage = BattlePokemon::apply_roll(base_damage, roll);
+		let crit_damage = (f64::from(damage) * rules.crit_multiplier).floor() as i32;
+		*outcomes.entry(damage).or_default() += roll_chance * (1.0 - crit_chance);
+		*outcomes.entry(crit_damage).or_default() += roll_chance * crit_chance;
+	}
+
+	outcomes.into_iter().filter(|it| it.1 > 0.0).collect()
+}
+
+/// The damage each end of turn status deals, paired with how many of the target's turns it
+/// will last for
+fn residual_damage(target: &BattlePokemon) -> Vec<(i32, i32)>
+{
+	let max_hp = target.effective_stats().hp;
+	target
+		.status_conditions()
+		.flat_map(|status| {
+			status.effects().filter_map(|eff| {
+				if let Effect::TurnEndDamageFraction { fraction_denominator } = eff
+				{
+					Some((max_hp / fraction_denominator, status.duration))
+				}
+				else
+				{
+					None
+				}
+			})
+		})
+		.collect()
+}
+
+fn spread(remaining: &BTreeMap<i32, f64>, outcomes: &[(i32, f64)]) -> BTreeMap<i32, f64>
+{
+	let mut next = BTreeMap::new();
+	for (&hp, &chance) in remaining
+	{
+		if hp == 0
+		{
+			*next.entry(0).or_default() += chance;
+			continue;
+		}
+		for &(damage, outcome_chance) in outcomes
+		{
+			*next.entry((hp - damage).max(0)).or_default() += chance * outcome_chance;
+		}
+	}
+
+	next
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{data::Pokemon, MOVE_MAP, RULESET, SPECIES_MAP, STATUS_MAP};
+
+	/// Leaves the target with one more hp than the move's highest roll without a crit can take
+	fn survive_one_hit(attacker: &BattlePokemon, target: &mut BattlePokemon, mv: &Move)
+	{
+		let base_damage = BattlePokemon::calculate_damage_no_roll(
+			attacker,
+			target,
+			&mv.power,
+			mv.category,
+			mv.move_type,
+			Style::Regular,
+		);
+		let hp = BattlePokemon::apply_roll(base_damage, RULESET.damage_rolls.end - 1) + 1;
+		assert!(hp < target.max_hp());
+		target.take_damage(target.max_hp() - hp);
+	}
+
+	fn assert_chances(chances: &KoChances, expected: &[f64])
+	{
+		assert_eq!(chances.within.len(), expected.len());
+		for (chance, expected) in chances.within.iter().zip(expected)
+		{
+			assert!((chance - expected).abs() < 1e-9, "{chance} is not {expected}");
+		}
+	}
+
+	#[test]
+	fn finds_the_chances_of_a_plain_move()
+	{
+		let machamp = Pokemon::new(&SPECIES_MAP["machamp"]).set_level(50);
+		let snorlax = Pokemon::new(&SPECIES_MAP["snorlax"]).set_level(70);
+		let attacker = BattlePokemon::new(&machamp, &RULESET);
+		let mut target = BattlePokemon::new(&snorlax, &RULESET);
+		let stone_edge = &MOVE_MAP["stone_edge"];
+		survive_one_hit(&attacker, &mut target, stone_edge);
+
+		// 80% accurate and crits one time in 8. only a crit knocks out in one hit, and any two
+		// hits do
+		let chances = ko_chances(&attacker, &target, stone_edge, Style::Regular, 2);
+		assert!((chances.hit_chance - 0.8).abs() < 1e-9);
+		assert!((chances.crit_chance - 0.125).abs() < 1e-9);
+		let survives_two = 0.2 * 0.2 + 2.0 * 0.2 * 0.8 * 0.875;
+		assert_chances(&chances, &[0.8 * 0.125, 1.0 - survives_two]);
+	}
+
+	#[test]
+	fn resolves_conditional_power_first()
+	{
+		let gengar = Pokemon::new(&SPECIES_MAP["gengar"]).set_level(40);
+		let rhyperior = Pokemon::new(&SPECIES_MAP["rhyperior"]).set_level(70);
+		let attacker = BattlePokemon::new(&gengar, &RULESET);
+		let mut target = BattlePokemon::new(&rhyperior, &RULESET);
+		let hex = &MOVE_MAP["hex"];
+		survive_one_hit(&attacker, &mut target, hex);
+
+		let chances = ko_chances(&attacker, &target, hex, Style::Regular, 2);
+		assert_chances(&chances, &[1.0 / 24.0, 1.0]);
+
+		// hex doubles in power against a paralysed target, enough for any hit to knock it out
+		target.apply_status(&STATUS_MAP["paralysis"], 3, hex);
+		let chances = ko_chances(&attacker, &target, hex, Style::Regular, 2);
+		assert_chances(&chances, &[1.0, 1.0]);
+	}
+}
@@ -0,0 +1,160 @@
+use crate::{battle, data};
+
+use data::{BattlePokemon, Move, Ruleset, Style, Team};
+
+#[derive(Debug, Clone)]
+pub struct MatchupCell
+{
+	pub defender: usize,
+	pub defender_hp: i32,
+	/// Damage before the random roll, as given by `calculate_damage_no_roll`
+	pub base_damage: i32,
+	pub min_damage: i32,
+	pub max_damage: i32,
+}
+impl MatchupCell
+{
+	pub fn min_percent(&self) -> f64
+	{
+		percent_of(self.min_damage, self.defender_hp)
+	}
+
+	pub fn max_percent(&self) -> f64
+	{
+		percent_of(self.max_damage, self.defender_hp)
+	}
+
+	/// Every damage value the random roll can produce, lowest first
+	pub fn rolls<'r>(&'r self, rules: &'r Ruleset) -> impl Iterator<Item = i32> + 'r
+	{
+		rules.damage_rolls.clone().map(|roll| BattlePokemon::apply_roll(self.base_damage, roll))
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchupRow<'a>
+{
+	pub attacker: usize,
+	pub mv: &'a Move<'a>,
+	pub style: Style,
+	/// One cell per member of the defending team, in team order
+	pub cells: Vec<MatchupCell>,
+}
+
+/// Every damaging move of every attacker, in each style, against every defender. Damage is
+/// calculated without crits, with both sides fresh and free of statuses, and each move resolved
+/// against the defender it's used on
+pub fn damage_matrix<'a>(
+	attackers: &Team<'a>,
+	defenders: &Team,
+	rules: &Ruleset,
+) -> Vec<MatchupRow<'a>>
+{
+	let defenders = defenders
+		.members
+		.iter()
+		.map(|it| BattlePokemon::new(it, rules))
+		.collect::<Vec<_>>();
+
+	let mut rows = Vec::new();
+	for (i, member) in attackers.members.iter().enumerate()
+	{
+		let attacker = BattlePokemon::new(member, rules);
+		let mut moves = member
+			.moves()
+			.filter(|it| it.category.is_damaging())
+			.collect::<Vec<_>>();
+		moves.sort_by(|a, b| a.id.cmp(&b.id));
+
+		for mv in moves
+		{
+			for style in Style::ALL
+			{
+				let cells = defenders
+					.iter()
+					.enumerate()
+					.map(|(j, defender)| {
+						let resolved = battle::resolve_move(&attacker, defender, mv);
+						let base_damage = BattlePokemon::calculate_damage_no_roll(
+							&attacker,
+							defender,
+							&resolved.power,
+							resolved.category,
+							resolved.move_type,
+							style,
+						);
+						MatchupCell {
+							defender: j,
+							defender_hp: defender.current_hp(),
+							base_damage,
+							min_damage: BattlePokemon::apply_roll(
+								base_damage,
+								rules.damage_rolls.start,
+							),
+							max_damage: BattlePokemon::apply_roll(
+								base_damage,
+								rules.damage_rolls.end - 1,
+							),
+						}
+					})
+					.collect();
+
+				rows.push(MatchupRow {
+					attacker: i,
+					mv,
+					style,
+					cells,
+				});
+			}
+		}
+	}
+
+	rows
+}
+
+fn percent_of(damage: i32, hp: i32) -> f64
+{
+	if hp == 0
+	{
+		0.0
+	}
+	else
+	{
+		f64::from(damage) * 100.0 / f64::from(hp)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{
+		data::{Pokemon, StatBlock},
+		MOVE_MAP, RULESET, SPECIES_MAP,
+	};
+
+	#[test]
+	fn calculates_damage_as_the_move_resolves()
+	{
+		let dialga = Team::new(String::from("Time")).add_member(
+			Pokemon::new(&SPECIES_MAP["dialga-origin"])
+				.set_effort_levels(StatBlock::all(0))
+				.add_move(&MOVE_MAP["roar_of_time"]),
+		);
+		let garchomp = Team::new(String::from("Sand")).add_member(
+			Pokemon::new(&SPECIES_MAP["garchomp"]).set_effort_levels(StatBlock::all(0)),
+		);
+
+		// 340 special attack against 210 special defense, with roar of time's power raised to
+		// 140 for origin forme dialga. ((100 + 340 + 15 * 100) * 140 / (210 + 50)) / 5 is 208,
+		// doubled for the weakness and a quarter more for stab
+		let rows = damage_matrix(&dialga, &garchomp, &RULESET);
+		let row = rows.iter().find(|it| it.style == Style::Regular).unwrap();
+		let cell = &row.cells[0];
+		assert_eq!(&*row.mv.id, "roar_of_time");
+		assert_eq!(
+			(cell.defender_hp, cell.base_damage, cell.min_damage, cell.max_damage),
+			(356, 520, 442, 514)
+		);
+	}
+}
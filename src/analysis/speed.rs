@@ -0,0 +1,112 @@
+use crate::data;
+
+use data::{Pokemon, Ruleset};
+
+#[derive(Debug, Clone)]
+pub struct SpeedEntry<'p, 'a>
+{
+	pub pokemon: &'p Pokemon<'a>,
+	pub speed: i32,
+	pub action_time: i32,
+	/// How much more speed it would take to reach the next faster tier, and that tier's action
+	/// time
+	pub to_next_tier: Option<(i32, i32)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpeedTier<'p, 'a>
+{
+	pub action_time: i32,
+	pub min_speed: Option<i32>,
+	pub max_speed: Option<i32>,
+	pub entries: Vec<SpeedEntry<'p, 'a>>,
+}
+
+/// Groups Pokémon into every action time tier, fastest first, with each tier sorted by speed
+pub fn speed_tiers<'p, 'a, I>(pokemon: I, rules: &Ruleset) -> Vec<SpeedTier<'p, 'a>>
+where
+	I: IntoIterator<Item = &'p Pokemon<'a>>,
+	'a: 'p,
+{
+	let slowest = (None, rules.slowest_action_time);
+	let bounds = std::iter::once(slowest)
+		.chain(rules.action_time_tiers.iter().map(|it| (Some(it.min_speed), it.action_time)))
+		.collect::<Vec<_>>();
+	let mut tiers = bounds
+		.iter()
+		.enumerate()
+		.map(|(i, (min_speed, action_time))| SpeedTier {
+			action_time: *action_time,
+			min_speed: *min_speed,
+			max_speed: bounds.get(i + 1).and_then(|it| it.0).map(|it| it - 1),
+			entries: Vec::new(),
+		})
+		.collect::<Vec<_>>();
+
+	for pokemon in pokemon
+	{
+		let speed = pokemon.stats().spe;
+		let entry = SpeedEntry {
+			pokemon,
+			speed,
+			action_time: rules.base_action_time(speed),
+			to_next_tier: rules
+				.next_action_time_tier(speed)
+				.map(|it| (it.min_speed - speed, it.action_time)),
+		};
+		if let Some(tier) = tiers
+			.iter_mut()
+			.find(|it| it.action_time == entry.action_time)
+		{
+			tier.entries.push(entry);
+		}
+	}
+
+	for tier in &mut tiers
+	{
+		tier.entries.sort_by_key(|it| std::cmp::Reverse(it.speed));
+	}
+	tiers.reverse();
+
+	tiers
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{RULESET, SPECIES_MAP};
+
+	/// Each entry's species, speed and distance to the next tier
+	type Summary<'t> = Vec<(&'t str, i32, Option<(i32, i32)>)>;
+
+	fn summary<'t>(tier: &'t SpeedTier) -> Summary<'t>
+	{
+		tier.entries
+			.iter()
+			.map(|it| (&*it.pokemon.species.id, it.speed, it.to_next_tier))
+			.collect()
+	}
+
+	#[test]
+	fn sorts_pokemon_into_their_tiers()
+	{
+		let pokemon = [("garchomp", 50), ("snorlax", 10), ("garchomp", 100), ("gengar", 50)]
+			.map(|(species, level)| Pokemon::new(&SPECIES_MAP[species]).set_level(level));
+		let tiers = speed_tiers(&pokemon, &RULESET);
+
+		let action_times = tiers.iter().map(|it| it.action_time).collect::<Vec<_>>();
+		assert_eq!(action_times, [5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+		assert_eq!((tiers[0].min_speed, tiers[0].max_speed), (Some(402), None));
+		assert_eq!((tiers[9].min_speed, tiers[9].max_speed), (None, Some(15)));
+
+		assert_eq!(summary(&tiers[1]), [("garchomp", 345, Some((57, 5)))]);
+		assert_eq!(
+			summary(&tiers[2]),
+			[("gengar", 271, Some((46, 6))), ("garchomp", 257, Some((60, 6)))]
+		);
+		assert_eq!((tiers[2].min_speed, tiers[2].max_speed), (Some(243), Some(316)));
+		assert!(tiers[3..6].iter().all(|it| it.entries.is_empty()));
+		assert_eq!(summary(&tiers[6]), [("snorlax", 83, Some((6, 10)))]);
+	}
+}
@@ -0,0 +1,1144 @@
+mod agent;
+mod batch;
+mod boss;
+mod chance;
+mod event;
+mod history;
+mod mcts;
+mod rating;
+mod save;
+mod search;
+mod tournament;
+
+pub use agent::*;
+pub use batch::*;
+pub use boss::*;
+pub use chance::*;
+pub use event::*;
+pub use history::*;
+pub use mcts::*;
+pub use rating::*;
+pub use save::*;
+pub use search::*;
+pub use tournament::*;
+
+use crate::{analysis, data};
+
+use data::{
+	BattlePokemon, DamageOrMaxHp, Effect, Move, MoveEffect, RegMap, Ruleset, Side, SpreadTarget,
+	StatusCondition, Style, Team,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+// whole battles are moved onto worker threads when playing batches
+const _: () = {
+	const fn assert_send_sync<T: Send + Sync>() {}
+	assert_send_sync::<Battle>();
+	assert_send_sync::<BattleEvent>();
+	assert_send_sync::<AgentFactory>();
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action<'a>
+{
+	Move
+	{
+		mv: &'a Move<'a>,
+		style: Style,
+		/// The pokemon the move is aimed at, which can be an ally. Spread moves hit everyone they
+		/// spread to, and are aimed at the first of them
+		target: Position,
+	},
+	/// Bring in the party member at this position, in place of the acting pokemon
+	Switch(usize),
+}
+
+/// Where a pokemon is on the field. Sides are 0 and 1, and each side has a slot for every
+/// pokemon it has out at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct Position
+{
+	pub side: usize,
+	pub slot: usize,
+}
+impl Position
+{
+	pub fn new(side: usize, slot: usize) -> Self
+	{
+		Self { side, slot }
+	}
+}
+impl std::fmt::Display for Position
+{
+	/// Side numbers count from 1 and slots are lettered, so `side 2b` is the second slot of the
+	/// second side
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let slot = u32::try_from(self.slot)
+			.ok()
+			.and_then(|it| char::from_digit(10 + it, 36))
+			.unwrap_or('?');
+		write!(f, "side {}{slot}", self.side + 1)
+	}
+}
+
+/// What the battle is waiting on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision
+{
+	/// The pokemon that acts next, decided by action time
+	Action(Position),
+	/// The side has to send out a pokemon in place of the one in this slot that fainted
+	Replacement(Position),
+	/// The winning side, or `None` for a draw
+	Finished(Option<usize>),
+}
+
+#[derive(Clone)]
+pub struct BattleSide<'a>
+{
+	pub party: Vec<BattlePokemon<'a>>,
+	/// The party member in each slot
+	pub active: Vec<usize>,
+}
+impl<'a> BattleSide<'a>
+{
+	pub fn active(&self, slot: usize) -> &BattlePokemon<'a>
+	{
+		&self.party[self.active[slot]]
+	}
+
+	fn active_mut(&mut self, slot: usize) -> &mut BattlePokemon<'a>
+	{
+		&mut self.party[self.active[slot]]
+	}
+
+	/// The slots whose pokemon haven't fainted, along with the pokemon
+	pub fn standing(&self) -> impl Iterator<Item = (usize, &BattlePokemon<'a>)>
+	{
+		(0..self.active.len())
+			.map(|slot| (slot, self.active(slot)))
+			.filter(|it| !it.1.is_fainted())
+	}
+
+	/// How many party members have not fainted
+	pub fn remaining(&self) -> usize
+	{
+		self.party.iter().filter(|it| !it.is_fainted()).count()
+	}
+
+	pub fn can_switch_to(&self, member: usize) -> bool
+	{
+		!self.active.contains(&member) && self.party.get(member).is_some_and(|it| !it.is_fainted())
+	}
+
+	/// Whether anyone is left to send out in place of a fainted pokemon
+	fn has_bench(&self) -> bool
+	{
+		(0..self.party.len()).any(|it| self.can_switch_to(it))
+	}
+}
+
+/// A battle between two teams sharing one action time timeline, with any number of pokemon out
+/// on each side. All randomness comes from the battle's own seeded generator, unless an action is
+/// applied with a [`ChanceOutcome`] fixing how its rolls go
+#[derive(Clone)]
+pub struct Battle<'a>
+{
+	sides: [BattleSide<'a>; 2],
+	status_map: &'a RegMap<StatusCondition<'a>>,
+	/// What a pokemon with no moves uses instead
+	struggle: &'a Move<'a>,
+	rules: &'a Ruleset,
+	/// The same generator as `StdRng`, named so it can be saved
+	rng: ChaCha12Rng,
+	/// The action time of whoever acted last
+	time: i32,
+	turn: u32,
+	events: Vec<BattleEvent>,
+	/// How the rolls of the action being applied go, in place of the generator
+	forced: Option<ChanceOutcome>,
+	/// Set in a noble battle
+	boss: Option<Boss<'a>>,
+}
+impl<'a> Battle<'a>
+{
+	/// A battle with one pokemon out on each side
+	pub fn new(
+		teams: [&'a Team<'a>; 2],
+		status_map: &'a RegMap<StatusCondition<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+		rules: &'a Ruleset,
+		seed: Option<u64>,
+	) -> Result<Self, BattleError>
+	{
+		Self::with_participants(teams, [1, 1], status_map, move_map, rules, seed)
+	}
+
+	/// A battle where each side starts with the first `participants` members of its team out,
+	/// like a trainer facing several wild pokemon at once. A side never has more out than its
+	/// team has members
+	pub fn with_participants(
+		teams: [&'a Team<'a>; 2],
+		participants: [usize; 2],
+		status_map: &'a RegMap<StatusCondition<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+		rules: &'a Ruleset,
+		seed: Option<u64>,
+	) -> Result<Self, BattleError>
+	{
+		if let Some(side) = teams.iter().position(|it| it.members.is_empty())
+		{
+			return Err(BattleError::EmptyTeam(side));
+		}
+		if let Some(side) = participants.iter().position(|it| *it == 0)
+		{
+			return Err(BattleError::NoParticipants(side));
+		}
+
+		let sides = std::array::from_fn(|side| {
+			let members = &teams[side].members;
+			BattleSide {
+				party: members.iter().map(|it| BattlePokemon::new(it, rules)).collect(),
+				active: (0..participants[side].min(members.len())).collect(),
+			}
+		});
+		let mut battle = Self {
+			sides,
+			status_map,
+			struggle: struggle(move_map)?,
+			rules,
+			rng: seed.map_or_else(ChaCha12Rng::from_entropy, ChaCha12Rng::seed_from_u64),
+			time: 0,
+			turn: 0,
+			events: Vec::new(),
+			forced: None,
+			boss: None,
+		};
+		for side in 0..2
+		{
+			for slot in 0..battle.sides[side].active.len()
+			{
+				battle.push_sent_out(Position::new(side, slot));
+			}
+		}
+
+		Ok(battle)
+	}
+
+	pub fn side(&self, side: usize) -> &BattleSide<'a>
+	{
+		&self.sides[side]
+	}
+
+	pub fn rules(&self) -> &'a Ruleset
+	{
+		self.rules
+	}
+
+	/// How many actions have been taken so far
+	pub fn turn(&self) -> u32
+	{
+		self.turn
+	}
+
+	pub fn events(&self) -> &[BattleEvent]
+	{
+		&self.events
+	}
+
+	pub fn decision(&self) -> Decision
+	{
+		if let Some(winner) = self.boss_winner()
+		{
+			return Decision::Finished(Some(winner));
+		}
+		match (self.sides[0].remaining(), self.sides[1].remaining())
+		{
+			(0, 0) => return Decision::Finished(None),
+			(0, _) => return Decision::Finished(Some(1)),
+			(_, 0) => return Decision::Finished(Some(0)),
+			_ => (),
+		}
+
+		self.positions()
+			.find(|it| {
+				let side = &self.sides[it.side];
+				side.active(it.slot).is_fainted() && side.has_bench()
+			})
+			.map_or_else(|| Decision::Action(self.next_actor()), Decision::Replacement)
+	}
+
+	/// Every action the side could legally take right now
+	pub fn legal_actions(&self, side: usize) -> Vec<Action<'a>>
+	{
+		let battle_side = &self.sides[side];
+		let switches = (0..battle_side.party.len())
+			.filter(|it| battle_side.can_switch_to(*it))
+			.map(Action::Switch);
+
+		match self.decision()
+		{
+			Decision::Replacement(it) if it.side == side => switches.collect(),
+			Decision::Action(it) if it.side == side =>
+			{
+				let mut moves = battle_side.active(it.slot).moves().collect::<Vec<_>>();
+				moves.sort_by(|a, b| a.id.cmp(&b.id));
+				// struggle only has a regular style
+				let styles: &[Style] = if moves.is_empty()
+				{
+					moves.push(self.struggle);
+					&[Style::Regular]
+				}
+				else
+				{
+					&Style::ALL
+				};
+				let mut actions = Vec::new();
+				for mv in moves
+				{
+					let targets = self.aim_options(it, mv);
+					for &style in styles
+					{
+						actions.extend(targets.iter().map(|target| Action::Move {
+							mv,
+							style,
+							target: *target,
+						}));
+					}
+				}
+				actions.extend(switches);
+				actions
+			}
+			_ => Vec::new(),
+		}
+	}
+
+	/// The pokemon in the order they are expected to act next, assuming every action from here
+	/// on costs each its base action time
+	pub fn predicted_order(&self, count: usize) -> Vec<Position>
+	{
+		let mut times = self
+			.standing_positions()
+			.map(|it| (it, self.pokemon_at(it).action_time()))
+			.collect::<Vec<_>>();
+		let mut order = Vec::with_capacity(count);
+		if times.is_empty()
+		{
+			return order;
+		}
+		for _ in 0..count
+		{
+			let next = (0..times.len())
+				.min_by_key(|it| self.turn_order_key(times[*it].0, times[*it].1))
+				.expect("there is someone to act");
+			let (position, time) = &mut times[next];
+			order.push(*position);
+			*time += self.pokemon_at(*position).base_action_time();
+		}
+
+		order
+	}
+
+	/// What the side gets to see. When the battle isn't waiting on the side, it's seen from the
+	/// side's first slot and has no actions
+	pub fn view(&self, side: usize) -> BattleView<'_, 'a>
+	{
+		let slot = match self.decision()
+		{
+			Decision::Action(it) | Decision::Replacement(it) if it.side == side => it.slot,
+			_ => 0,
+		};
+		let own = &self.sides[side];
+		let opponent = &self.sides[1 - side];
+		BattleView {
+			side,
+			slot,
+			party: &own.party,
+			active: own.active[slot],
+			opponents: opponent.standing().collect(),
+			opponents_remaining: opponent.remaining(),
+			turn_order: self.predicted_order(4),
+			actions: self.legal_actions(side),
+			battle: self,
+		}
+	}
+
+	/// Every pokemon the move hits when aimed at `target`
+	pub fn targets_of(&self, user: Position, mv: &Move, target: Position) -> Vec<Position>
+	{
+		match self.rules.spread.target_of(&mv.id)
+		{
+			Some(SpreadTarget::Foes) => self
+				.standing_positions()
+				.filter(|it| it.side != user.side)
+				.collect(),
+			Some(SpreadTarget::AllOthers) => self
+				.standing_positions()
+				.filter(|it| *it != user)
+				.collect(),
+			None => vec![target],
+		}
+	}
+
+	/// Who the pokemon can aim the move at. Spread moves only get aimed one way, since they hit
+	/// the same pokemon whichever way they're aimed
+	fn aim_options(&self, user: Position, mv: &Move) -> Vec<Position>
+	{
+		let mut targets = self
+			.standing_positions()
+			.filter(|it| *it != user)
+			.collect::<Vec<_>>();
+		// foes before allies
+		targets.sort_by_key(|it| it.side == user.side);
+		if self.rules.spread.target_of(&mv.id).is_some()
+		{
+			let first = self.targets_of(user, mv, user).first().copied();
+			targets.retain(|it| Some(*it) == first);
+		}
+
+		targets
+	}
+
+	/// The active pokemon at the position
+	pub fn pokemon_at(&self, position: Position) -> &BattlePokemon<'a>
+	{
+		self.sides[position.side].active(position.slot)
+	}
+
+	/// Carries out the action for whichever pokemon the battle is waiting on
+	pub fn apply(&mut self, action: Action<'a>) -> Result<(), BattleError>
+	{
+		let (position, is_replacement) = match self.decision()
+		{
+			Decision::Finished(_) => return Err(BattleError::Finished),
+			Decision::Action(it) => (it, false),
+			Decision::Replacement(it) => (it, true),
+		};
+		if !self.legal_actions(position.side).contains(&action)
+		{
+			return Err(BattleError::IllegalAction(position.side));
+		}
+
+		if is_replacement
+		{
+			if let Action::Switch(member) = action
+			{
+				self.switch_in(position, member);
+			}
+			return Ok(());
+		}
+
+		self.time = self.pokemon_at(position).action_time();
+		self.turn += 1;
+		if let Some(status_id) = self.roll_cancel(position)
+		{
+			self.events.push(BattleEvent::TurnCancelled {
+				position,
+				status_id,
+			});
+			let actor = self.sides[position.side].active_mut(position.slot);
+			actor.add_action_time(actor.base_action_time());
+		}
+		else
+		{
+			match action
+			{
+				Action::Move { mv, style, target } => self.use_move(position, target, mv, style),
+				Action::Switch(member) => self.switch_in(position, member),
+			}
+		}
+		self.end_turn(position);
+		self.push_faints();
+		self.update_boss_phase();
+
+		if let Decision::Finished(winner) = self.decision()
+		{
+			self.events.push(winner.map_or(BattleEvent::Draw, |side| BattleEvent::Won { side }));
+		}
+
+		Ok(())
+	}
+
+	/// Plays the battle out with an agent for each side, giving up after `max_turns` actions.
+	/// Returns the winning side, or `None` for a draw or running out of turns
+	pub fn run(
+		&mut self,
+		agents: &mut [&mut dyn Agent; 2],
+		max_turns: u32,
+	) -> Result<Option<usize>, BattleError>
+	{
+		loop
+		{
+			let side = match self.decision()
+			{
+				Decision::Finished(winner) => return Ok(winner),
+				_ if self.turn >= max_turns => return Ok(None),
+				Decision::Action(it) | Decision::Replacement(it) => it.side,
+			};
+			let action = agents[side].choose(&self.view(side));
+			self.apply(action)?;
+		}
+	}
+
+	/// Every slot on the field, side 0 first
+	fn positions(&self) -> impl Iterator<Item = Position> + '_
+	{
+		(0..2).flat_map(|side| {
+			(0..self.sides[side].active.len()).map(move |slot| Position::new(side, slot))
+		})
+	}
+
+	fn standing_positions(&self) -> impl Iterator<Item = Position> + '_
+	{
+		self.positions().filter(|it| !self.pokemon_at(*it).is_fainted())
+	}
+
+	fn next_actor(&self) -> Position
+	{
+		self.standing_positions()
+			.min_by_key(|it| self.turn_order_key(*it, self.pokemon_at(*it).action_time()))
+			.expect("a battle that isn't over has someone standing")
+	}
+
+	/// Lowest action time goes first, then highest speed, then side 0, then the earlier slot
+	fn turn_order_key(
+		&self,
+		position: Position,
+		time: i32,
+	) -> (i32, std::cmp::Reverse<i32>, usize, usize)
+	{
+		(
+			time,
+			std::cmp::Reverse(self.pokemon_at(position).effective_stats().spe),
+			position.side,
+			position.slot,
+		)
+	}
+
+	fn switch_in(&mut self, position: Position, member: usize)
+	{
+		let battle_side = &mut self.sides[position.side];
+		battle_side.active[position.slot] = member;
+		let incoming = battle_side.active_mut(position.slot);
+		incoming.set_action_time(self.time + incoming.base_action_time());
+		self.push_sent_out(position);
+	}
+
+	fn push_sent_out(&mut self, position: Position)
+	{
+		let battle_side = &self.sides[position.side];
+		let pokemon = battle_side.active(position.slot).pokemon;
+		self.events.push(BattleEvent::SentOut {
+			position,
+			member: battle_side.active[position.slot],
+			species: pokemon.species.id.clone(),
+			is_alpha: pokemon.is_alpha,
+		});
+	}
+
+	/// The status that stops the pokemon from moving this turn, if any
+	fn roll_cancel(&mut self, position: Position) -> Option<Box<str>>
+	{
+		self.sides[position.side]
+			.active(position.slot)
+			.status_conditions()
+			.flat_map(|status| {
+				status.effects().filter_map(move |eff| {
+					if let Effect::CancelTurn { chance } = eff
+					{
+						Some((status.condition.id.clone(), *chance))
+					}
+					else
+					{
+						None
+					}
+				})
+			})
+			.find(|(_, chance)| match self.forced
+			{
+				Some(outcome) => outcome.cancelled,
+				None => self.rng.gen_range(0..100) < *chance,
+			})
+			.map(|it| it.0)
+	}
+
+	fn use_move(
+		&mut self,
+		user_position: Position,
+		aimed_at: Position,
+		mv: &'a Move<'a>,
+		style: Style,
+	)
+	{
+		let targets = self.targets_of(user_position, mv, aimed_at);
+		let spread_multiplier = if targets.len() > 1
+		{
+			self.rules.spread.multiplier
+		}
+		else
+		{
+			1.0
+		};
+
+		self.events.push(BattleEvent::UsedMove {
+			user: user_position,
+			target: aimed_at,
+			move_id: mv.id.clone(),
+			style,
+		});
+		let (user, aimed) = pair_mut(&mut self.sides, user_position, aimed_at);
+		let user_action_time = resolve_move(user, aimed, mv).user_action_time[style];
+		user.add_action_time(user.base_action_time() + user_action_time);
+
+		let mut total_dealt = 0;
+		let mut first_hit = None;
+		for target_position in targets
+		{
+			let (user, target) = pair_mut(&mut self.sides, user_position, target_position);
+			let resolved = resolve_move(user, target, mv);
+			target.add_action_time(resolved.target_action_time[style]);
+
+			let hits = match self.forced
+			{
+				Some(outcome) => outcome.hit,
+				None => self.rng.gen_bool(analysis::hit_chance(target, &resolved, style)),
+			};
+			if !hits
+			{
+				self.events.push(BattleEvent::Missed {
+					user: user_position,
+					target: target_position,
+				});
+				continue;
+			}
+
+			let dealt = self.deal_damage(
+				[user_position, target_position],
+				&resolved,
+				style,
+				spread_multiplier,
+			);
+			total_dealt += dealt;
+			first_hit.get_or_insert(target_position);
+			self.apply_move_effects(
+				[user_position, target_position],
+				mv,
+				&resolved,
+				style,
+				Side::Target,
+				dealt,
+			);
+		}
+
+		// effects on the user happen once however many pokemon were hit
+		if let Some(target_position) = first_hit
+		{
+			let (user, target) = pair_mut(&mut self.sides, user_position, target_position);
+			let resolved = resolve_move(user, target, mv);
+			self.apply_move_effects(
+				[user_position, target_position],
+				mv,
+				&resolved,
+				style,
+				Side::User,
+				total_dealt,
+			);
+		}
+	}
+
+	/// Rolls for a crit and damage and deals it, returning how much was dealt
+	fn deal_damage(
+		&mut self,
+		[user_position, target_position]: [Position; 2],
+		resolved: &Move,
+		style: Style,
+		multiplier: f64,
+	) -> i32
+	{
+		if !resolved.category.is_damaging()
+		{
+			return 0;
+		}
+
+		let (user, target) = pair_mut(&mut self.sides, user_position, target_position);
+		let base_damage = BattlePokemon::calculate_damage_no_roll(
+			user,
+			target,
+			&resolved.power,
+			resolved.category,
+			resolved.move_type,
+			style,
+		);
+		let (is_crit, roll) = match self.forced
+		{
+			Some(outcome) => (outcome.crit, outcome.damage_roll),
+			None => (
+				self.rng
+					.gen_range(0..user.crit_chance_denominator(resolved, style))
+					== 0,
+				self.rng.gen_range(self.rules.damage_rolls.clone()),
+			),
+		};
+		let mut damage = f64::from(BattlePokemon::apply_roll(base_damage, roll)) * multiplier;
+		if is_crit
+		{
+			damage *= self.rules.crit_multiplier;
+		}
+
+		let dealt = target.take_damage(damage.floor() as i32);
+		self.events.push(BattleEvent::Damaged {
+			position: target_position,
+			amount: dealt,
+			is_crit,
+			effectiveness: target.types().damage_multiplier_from(resolved.move_type),
+		});
+		dealt
+	}
+
+	/// The move's effects on either the user or the target besides damage. `dealt` is how much
+	/// damage it did
+	fn apply_move_effects(
+		&mut self,
+		[user_position, target_position]: [Position; 2],
+		mv: &'a Move<'a>,
+		resolved: &Move,
+		style: Style,
+		scope: Side,
+		dealt: i32,
+	)
+	{
+		let (user, target) = pair_mut(&mut self.sides, user_position, target_position);
+		for effect in &*resolved.effects
+		{
+			if effect_side(effect) != Some(scope) || !effect.condition().is_met(user, target)
+			{
+				continue;
+			}
+
+			let (pokemon, position) = match scope
+			{
+				Side::User => (&mut *user, user_position),
+				Side::Target => (&mut *target, target_position),
+			};
+			match effect
+			{
+				MoveEffect::ApplyStatus {
+					status_option_ids,
+					duration,
+					chance,
+					..
+				} =>
+				{
+					let Some(option) = roll_status_option(
+						&mut self.rng,
+						self.forced,
+						chance[style],
+						status_option_ids.len(),
+					)
+					else
+					{
+						continue;
+					};
+					let id = &status_option_ids[option];
+					let Some(condition) = self.status_map.get(id)
+					else
+					{
+						continue;
+					};
+					if !pokemon.is_fainted()
+					{
+						pokemon.apply_status(condition, duration[style], mv);
+						if pokemon.has_status(id)
+						{
+							self.events.push(BattleEvent::StatusApplied {
+								position,
+								status_id: id.clone(),
+							});
+						}
+					}
+				}
+				MoveEffect::CureStatus { status_ids, .. } =>
+				{
+					for id in &**status_ids
+					{
+						if pokemon.cure_status(id)
+						{
+							self.events.push(BattleEvent::StatusCured {
+								position,
+								status_id: id.clone(),
+							});
+						}
+					}
+				}
+				MoveEffect::Heal {
+					percent_of,
+					percent,
+					..
+				} =>
+				{
+					let amount =
+						percent_amount(*percent_of, percent[style], dealt, pokemon.max_hp());
+					let amount = pokemon.heal(amount);
+					if amount > 0
+					{
+						self.events.push(BattleEvent::Healed { position, amount });
+					}
+				}
+				MoveEffect::Recoil {
+					percent_of,
+					percent,
+					..
+				} =>
+				{
+					let amount =
+						percent_amount(*percent_of, percent[style], dealt, pokemon.max_hp());
+					let amount = pokemon.take_damage(amount);
+					if amount > 0
+					{
+						self.events.push(BattleEvent::Recoil { position, amount });
+					}
+				}
+				MoveEffect::MultiplyPower { .. }
+				| MoveEffect::ModifyData { .. }
+				| MoveEffect::SwapOffenseAndDefense { .. } => (),
+			}
+		}
+	}
+
+	/// Damage from statuses and ticking their durations down, for the pokemon that just acted
+	fn end_turn(&mut self, position: Position)
+	{
+		let actor = self.sides[position.side].active_mut(position.slot);
+		if actor.is_fainted()
+		{
+			return;
+		}
+
+		let max_hp = actor.max_hp();
+		let residuals = actor
+			.status_conditions()
+			.flat_map(|status| {
+				status.effects().filter_map(move |eff| {
+					// TODO: turn_end_damage_move needs to know who inflicted the status, which
+					// statuses don't keep track of yet
+					if let Effect::TurnEndDamageFraction {
+						fraction_denominator,
+					} = eff
+					{
+						Some((status.condition.id.clone(), max_hp / fraction_denominator))
+					}
+					else
+					{
+						None
+					}
+				})
+			})
+			.collect::<Vec<_>>();
+
+		for (status_id, amount) in residuals
+		{
+			let amount = actor.take_damage(amount);
+			if amount > 0
+			{
+				self.events.push(BattleEvent::StatusDamage {
+					position,
+					status_id,
+					amount,
+				});
+			}
+		}
+		actor.tick_statuses();
+	}
+
+	/// Records a faint for every active pokemon that went down since the last check
+	fn push_faints(&mut self)
+	{
+		let faints = self
+			.positions()
+			.filter(|it| self.pokemon_at(*it).is_fainted())
+			.map(|position| BattleEvent::Fainted {
+				position,
+				member: self.sides[position.side].active[position.slot],
+			})
+			.filter(|faint| !self.events.iter().rev().any(|it| it == faint))
+			.collect::<Vec<_>>();
+		self.events.extend(faints);
+	}
+}
+
+/// What the agent playing one side gets to see
+pub struct BattleView<'b, 'a>
+{
+	pub side: usize,
+	/// The slot of the pokemon acting or being replaced
+	pub slot: usize,
+	pub party: &'b [BattlePokemon<'a>],
+	/// The party member in the slot
+	pub active: usize,
+	/// The opposing pokemon that haven't fainted, with the slot each is in
+	pub opponents: Vec<(usize, &'b BattlePokemon<'a>)>,
+	pub opponents_remaining: usize,
+	/// The pokemon expected to act next, soonest first
+	pub turn_order: Vec<Position>,
+	/// Every action that can be taken, which is only switches when replacing a fainted pokemon
+	pub actions: Vec<Action<'a>>,
+	/// The whole battle, for agents that search ahead. This shows the opposing party as well, so
+	/// those agents play with full information
+	pub battle: &'b Battle<'a>,
+}
+impl<'b, 'a> BattleView<'b, 'a>
+{
+	pub fn own_active(&self) -> &'b BattlePokemon<'a>
+	{
+		&self.party[self.active]
+	}
+
+	/// Whether a fainted pokemon is being replaced, rather than taking a turn
+	pub fn is_replacement(&self) -> bool
+	{
+		self.own_active().is_fainted()
+	}
+
+	/// Where the pokemon acting or being replaced is
+	pub fn position(&self) -> Position
+	{
+		Position::new(self.side, self.slot)
+	}
+}
+
+/// The move a pokemon with no moves of its own uses
+fn struggle<'a>(move_map: &'a RegMap<Move<'a>>) -> Result<&'a Move<'a>, BattleError>
+{
+	move_map
+		.get("struggle")
+		.ok_or_else(|| BattleError::MissingId("struggle".into()))
+}
+
+/// The active pokemon at two different positions
+fn pair_mut<'s, 'a>(
+	sides: &'s mut [BattleSide<'a>; 2],
+	first: Position,
+	second: Position,
+) -> (&'s mut BattlePokemon<'a>, &'s mut BattlePokemon<'a>)
+{
+	let [side_0, side_1] = sides;
+	match (first.side, second.side)
+	{
+		(0, 1) => (side_0.active_mut(first.slot), side_1.active_mut(second.slot)),
+		(1, 0) => (side_1.active_mut(first.slot), side_0.active_mut(second.slot)),
+		(side, _) =>
+		{
+			let battle_side = if side == 0 { side_0 } else { side_1 };
+			let [first, second] = [first, second].map(|it| battle_side.active[it.slot]);
+			assert_ne!(first, second, "a pokemon can't be paired with itself");
+			if first < second
+			{
+				let (left, right) = battle_side.party.split_at_mut(second);
+				(&mut left[first], &mut right[0])
+			}
+			else
+			{
+				let (left, right) = battle_side.party.split_at_mut(first);
+				(&mut right[0], &mut left[second])
+			}
+		}
+	}
+}
+
+/// Whether the effect acts on the user or on each pokemon the move hits, or `None` if it doesn't
+/// act on either directly. Power and data changes are worked into the resolved move instead, and
+/// swapping stats is handled by the stance swap status
+fn effect_side(effect: &MoveEffect) -> Option<Side>
+{
+	match effect
+	{
+		MoveEffect::ApplyStatus { to: side, .. } | MoveEffect::CureStatus { of: side, .. } =>
+		{
+			Some(*side)
+		}
+		MoveEffect::Heal { .. } | MoveEffect::Recoil { .. } => Some(Side::User),
+		MoveEffect::MultiplyPower { .. }
+		| MoveEffect::ModifyData { .. }
+		| MoveEffect::SwapOffenseAndDefense { .. } => None,
+	}
+}
+
+/// Which of a move's status options takes hold, if any
+fn roll_status_option(
+	rng: &mut ChaCha12Rng,
+	forced: Option<ChanceOutcome>,
+	chance: i32,
+	options: usize,
+) -> Option<usize>
+{
+	if options == 0
+	{
+		return None;
+	}
+
+	match forced
+	{
+		Some(outcome) => outcome.status_applies.then_some(0),
+		None => (rng.gen_range(0..100) < chance).then(|| rng.gen_range(0..options)),
+	}
+}
+
+/// The move with every power multiplier and data change whose condition is met worked in
+pub fn resolve_move<'a>(user: &BattlePokemon, target: &BattlePokemon, mv: &Move<'a>) -> Move<'a>
+{
+	let mut resolved = mv.clone();
+	for effect in &*mv.effects
+	{
+		if !effect.condition().is_met(user, target)
+		{
+			continue;
+		}
+
+		match effect
+		{
+			MoveEffect::MultiplyPower { multiplier, .. } =>
+			{
+				let power = &resolved.power;
+				resolved.power = data::StyleTriad::new(
+					power.regular * multiplier,
+					power.agile * multiplier,
+					power.strong * multiplier,
+				);
+			}
+			MoveEffect::ModifyData {
+				power,
+				accuracy,
+				user_action_time,
+				target_action_time,
+				crit_stage,
+				..
+			} =>
+			{
+				let replace = |field: &mut data::StyleTriad<i32>, value: &Option<_>| {
+					if let Some(value) = value
+					{
+						field.clone_from(value);
+					}
+				};
+				replace(&mut resolved.power, power);
+				replace(&mut resolved.accuracy, accuracy);
+				replace(&mut resolved.user_action_time, user_action_time);
+				replace(&mut resolved.target_action_time, target_action_time);
+				replace(&mut resolved.crit_stage, crit_stage);
+			}
+			_ => (),
+		}
+	}
+
+	resolved
+}
+
+fn percent_amount(percent_of: DamageOrMaxHp, percent: i32, dealt: i32, max_hp: i32) -> i32
+{
+	match percent_of
+	{
+		DamageOrMaxHp::DamageDealt => dealt * percent / 100,
+		DamageOrMaxHp::MaxHp => max_hp * percent / 100,
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BattleError
+{
+	EmptyTeam(usize),
+	NoParticipants(usize),
+	IllegalAction(usize),
+	Finished,
+	/// A saved battle names something the dex doesn't have
+	MissingId(Box<str>),
+	/// A saved side doesn't fit the team it's loaded with
+	PartyMismatch(usize),
+}
+impl std::fmt::Display for BattleError
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			Self::EmptyTeam(side) => write!(f, "side {} has no pokemon", side + 1),
+			Self::NoParticipants(side) => write!(f, "side {} has no pokemon out", side + 1),
+			Self::IllegalAction(side) => write!(f, "side {} chose an action it can't take", side + 1),
+			Self::Finished => write!(f, "the battle is already over"),
+			Self::MissingId(id) => write!(f, "'{id}' is not in the dex"),
+			Self::PartyMismatch(side) =>
+			{
+				write!(f, "side {} doesn't match the team it was saved with", side + 1)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{data::{BossRules, Pokemon}, MOVE_MAP, RULESET, SPECIES_MAP, STATUS_MAP};
+
+	#[test]
+	fn struggles_without_any_moves()
+	{
+		let team = Team::new(String::from("Moveless"))
+			.add_member(Pokemon::new(&SPECIES_MAP["garchomp"]).set_level(50));
+		let battle =
+			Battle::new([&team, &team], &STATUS_MAP, &MOVE_MAP, &RULESET, Some(1)).unwrap();
+
+		let Decision::Action(actor) = battle.decision()
+		else
+		{
+			panic!("the first pokemon to act should get to move");
+		};
+		assert_eq!(
+			battle.legal_actions(actor.side),
+			[Action::Move {
+				mv: &MOVE_MAP["struggle"],
+				style: Style::Regular,
+				target: Position::new(1 - actor.side, 0),
+			}]
+		);
+
+		let agents: [[Box<dyn Agent>; 2]; 2] = [
+			[Box::new(RandomAgent::new(Some(1))), Box::new(GreedyAgent)],
+			[
+				Box::new(ExpectiminimaxAgent::new(1)),
+				Box::new(MctsAgent::new(Budget::Iterations(20), Some(1))),
+			],
+		];
+		for [mut first, mut second] in agents
+		{
+			let mut battle = battle.clone();
+			battle.run(&mut [&mut *first, &mut *second], 50).unwrap();
+			assert!(battle.events().iter().any(|it| matches!(
+				it,
+				BattleEvent::UsedMove { move_id, .. } if &**move_id == "struggle"
+			)));
+		}
+	}
+
+	#[test]
+	fn starts_the_boss_at_its_scaled_action_time()
+	{
+		let team = Team::new(String::from("Noble"))
+			.add_member(Pokemon::new(&SPECIES_MAP["garchomp"]).set_level(50));
+		let rules = BossRules {
+			hp_multiplier: 2.0,
+			stat_multiplier: 2.0,
+			phases: Box::new([]),
+			faints_allowed: None,
+			turn_limit: None,
+		};
+		let battle = Battle::new([&team, &team], &STATUS_MAP, &MOVE_MAP, &RULESET, Some(1))
+			.and_then(|it| it.with_boss(rules, &MOVE_MAP))
+			.unwrap();
+
+		let boss = battle.pokemon_at(BOSS_POSITION);
+		assert_eq!(boss.action_time(), boss.base_action_time());
+		assert!(boss.action_time() < battle.pokemon_at(Position::new(0, 0)).action_time());
+	}
+}
@@ -0,0 +1,216 @@
+use crate::{analysis, battle, data};
+
+use battle::{Action, BattleView, Position};
+use data::{BattlePokemon, Move, Style};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+/// Something that decides what one side of a battle does
+pub trait Agent
+{
+	/// Picks one of `view.actions`, which is never empty
+	fn choose<'a>(&mut self, view: &BattleView<'_, 'a>) -> Action<'a>;
+}
+
+/// Picks any legal action with equal chance
+pub struct RandomAgent
+{
+	rng: StdRng,
+}
+impl RandomAgent
+{
+	pub fn new(seed: Option<u64>) -> Self
+	{
+		Self {
+			rng: seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+		}
+	}
+}
+impl Agent for RandomAgent
+{
+	fn choose<'a>(&mut self, view: &BattleView<'_, 'a>) -> Action<'a>
+	{
+		*view
+			.actions
+			.choose(&mut self.rng)
+			.expect("there is always an action to take")
+	}
+}
+
+/// Always uses the move, style and target with the most expected damage, and replaces fainted
+/// pokemon with whichever could deal the most
+pub struct GreedyAgent;
+impl Agent for GreedyAgent
+{
+	fn choose<'a>(&mut self, view: &BattleView<'_, 'a>) -> Action<'a>
+	{
+		if view.is_replacement()
+		{
+			return best_by(&view.actions, |action| match action
+			{
+				Action::Switch(member) => view
+					.opponents
+					.iter()
+					.map(|it| best_damage(&view.party[*member], it.1))
+					.fold(0.0, f64::max),
+				Action::Move { .. } => 0.0,
+			});
+		}
+
+		best_by(&view.actions, |action| match action
+		{
+			Action::Move { mv, style, target } => move_damage(view, mv, *style, *target),
+			Action::Switch(_) => -1.0,
+		})
+	}
+}
+
+/// Plays around types. It switches out when the opposing pokemon's types threaten the active
+/// pokemon and something in the party stands up to them better, and otherwise attacks with
+/// whatever deals the most damage for the action time it costs
+pub struct HeuristicAgent;
+impl Agent for HeuristicAgent
+{
+	fn choose<'a>(&mut self, view: &BattleView<'_, 'a>) -> Action<'a>
+	{
+		let threat_to = |pokemon: &BattlePokemon| {
+			view.opponents
+				.iter()
+				.map(|it| threat(it.1, pokemon))
+				.fold(0.0, f64::max)
+		};
+		let matchup = |member: usize| {
+			let pokemon = &view.party[member];
+			let best_offense = view
+				.opponents
+				.iter()
+				.map(|it| offense(pokemon, it.1))
+				.fold(0.0, f64::max);
+			best_offense - threat_to(pokemon)
+		};
+
+		if view.is_replacement()
+		{
+			return best_by(&view.actions, |action| match action
+			{
+				Action::Switch(member) => matchup(*member),
+				Action::Move { .. } => f64::MIN,
+			});
+		}
+
+		let best_switch = view
+			.actions
+			.iter()
+			.filter_map(|action| match action
+			{
+				Action::Switch(member) => Some((*action, matchup(*member))),
+				Action::Move { .. } => None,
+			})
+			.max_by(|a, b| a.1.total_cmp(&b.1));
+		if let Some((action, score)) = best_switch
+			&& threat_to(view.own_active()) > 1.0
+			&& score > matchup(view.active) + 1.0
+		{
+			return action;
+		}
+
+		let active = view.own_active();
+		best_by(&view.actions, |action| match action
+		{
+			Action::Move { mv, style, target } =>
+			{
+				let resolved = battle::resolve_move(active, view.battle.pokemon_at(*target), mv);
+				let cost = active.base_action_time() + resolved.user_action_time[*style]
+					- resolved.target_action_time[*style];
+				move_damage(view, mv, *style, *target) / f64::from(cost.max(1))
+			}
+			Action::Switch(_) => -1.0,
+		})
+	}
+}
+
+/// The first action with the highest score
+pub(super) fn best_by<'a, F>(actions: &[Action<'a>], mut score: F) -> Action<'a>
+where
+	F: FnMut(&Action<'a>) -> f64,
+{
+	let mut best = actions[0];
+	let mut best_score = score(&best);
+	for action in &actions[1..]
+	{
+		let action_score = score(action);
+		if action_score > best_score
+		{
+			best = *action;
+			best_score = action_score;
+		}
+	}
+
+	best
+}
+
+/// Expected damage to every pokemon the move hits when aimed at `target`, with damage to allies
+/// counting against it
+fn move_damage(view: &BattleView, mv: &Move, style: Style, target: Position) -> f64
+{
+	let targets = view.battle.targets_of(view.position(), mv, target);
+	let spread = if targets.len() > 1 { view.battle.rules().spread.multiplier } else { 1.0 };
+	targets
+		.into_iter()
+		.map(|it| {
+			let damage = expected_damage(view.own_active(), view.battle.pokemon_at(it), mv, style);
+			if it.side == view.side { -damage } else { damage }
+		})
+		.sum::<f64>()
+		* spread
+}
+
+/// Average damage from accuracy and the unrolled damage of the move as it resolves against the
+/// target, ignoring crits
+fn expected_damage(attacker: &BattlePokemon, target: &BattlePokemon, mv: &Move, style: Style)
+	-> f64
+{
+	if !mv.category.is_damaging()
+	{
+		return 0.0;
+	}
+
+	let mv = &battle::resolve_move(attacker, target, mv);
+	let damage = BattlePokemon::calculate_damage_no_roll(
+		attacker,
+		target,
+		&mv.power,
+		mv.category,
+		mv.move_type,
+		style,
+	);
+	f64::from(damage) * analysis::hit_chance(target, mv, style)
+}
+
+fn best_damage(attacker: &BattlePokemon, target: &BattlePokemon) -> f64
+{
+	attacker
+		.moves()
+		.flat_map(|mv| Style::ALL.map(|style| expected_damage(attacker, target, mv, style)))
+		.fold(0.0, f64::max)
+}
+
+/// The best type multiplier the attacker's damaging moves get against the target
+fn offense(attacker: &BattlePokemon, target: &BattlePokemon) -> f64
+{
+	attacker
+		.moves()
+		.filter(|mv| mv.category.is_damaging())
+		.map(|mv| target.types().damage_multiplier_from(mv.move_type))
+		.fold(0.0, f64::max)
+}
+
+/// The worst multiplier the target takes from the attacker's own types, standing in for moves
+/// the attacker hasn't shown yet
+fn threat(attacker: &BattlePokemon, target: &BattlePokemon) -> f64
+{
+	attacker
+		.types()
+		.iter()
+		.map(|typ| target.types().damage_multiplier_from(typ))
+		.fold(0.0, f64::max)
+}
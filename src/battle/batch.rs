@@ -0,0 +1,251 @@
+use crate::{battle, data};
+
+use battle::{AgentFactory, Battle, BattleError, BattleEvent};
+use data::{Move, RegMap, Ruleset, StatusCondition, Team};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How one game went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct GameResult
+{
+	pub winner: Option<usize>,
+	pub turns: u32,
+	/// Damage from moves each side dealt to the other
+	pub damage_dealt: [u64; 2],
+}
+
+/// Plays out one battle between the teams with a fresh agent for each side. The battle is seeded
+/// with `seed` and the agents with the two seeds after it, so games seeded with [`game_seed`]
+/// don't share any random numbers
+#[allow(clippy::too_many_arguments)]
+pub fn play_game(
+	teams: [&Team; 2],
+	agents: [&AgentFactory; 2],
+	participants: [usize; 2],
+	status_map: &RegMap<StatusCondition>,
+	move_map: &RegMap<Move>,
+	rules: &Ruleset,
+	seed: Option<u64>,
+	max_turns: u32,
+) -> Result<GameResult, BattleError>
+{
+	let mut battle =
+		Battle::with_participants(teams, participants, status_map, move_map, rules, seed)?;
+	let [mut first, mut second] =
+		[0, 1].map(|side| agents[side](seed.map(|it| it.wrapping_add(side as u64 + 1))));
+	let winner = battle.run(&mut [&mut *first, &mut *second], max_turns)?;
+
+	let mut damage_dealt = [0u64; 2];
+	for event in battle.events()
+	{
+		if let BattleEvent::Damaged {
+			position, amount, ..
+		} = event
+		{
+			damage_dealt[1 - position.side] += u64::try_from(*amount).unwrap_or_default();
+		}
+	}
+
+	Ok(GameResult {
+		winner,
+		turns: battle.turn(),
+		damage_dealt,
+	})
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BatchOptions
+{
+	pub games: u32,
+	/// Actions after which a game counts as a draw
+	pub max_turns: u32,
+	/// How many pokemon each side has out at once
+	pub participants: [usize; 2],
+	/// Seeds every game through [`game_seed`], so a batch plays out the same however many threads
+	/// it runs on
+	pub seed: Option<u64>,
+	/// Worker threads to spread the games over, at least 1
+	pub threads: usize,
+}
+
+/// Totals over a batch of games between the same two sides
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct BatchResult
+{
+	pub games: Vec<GameResult>,
+	pub wins: [u32; 2],
+	pub draws: u32,
+	pub total_turns: u64,
+	pub damage_dealt: [u64; 2],
+}
+impl BatchResult
+{
+	pub fn win_rate(&self, side: usize) -> f64
+	{
+		f64::from(self.wins[side]) / self.games.len().max(1) as f64
+	}
+
+	pub fn average_turns(&self) -> f64
+	{
+		self.total_turns as f64 / self.games.len().max(1) as f64
+	}
+}
+impl FromIterator<GameResult> for BatchResult
+{
+	fn from_iter<I: IntoIterator<Item = GameResult>>(iter: I) -> Self
+	{
+		let mut result = Self {
+			games: Vec::new(),
+			wins: [0; 2],
+			draws: 0,
+			total_turns: 0,
+			damage_dealt: [0; 2],
+		};
+		for game in iter
+		{
+			match game.winner
+			{
+				Some(side) => result.wins[side] += 1,
+				None => result.draws += 1,
+			}
+			result.total_turns += u64::from(game.turns);
+			result.damage_dealt[0] += game.damage_dealt[0];
+			result.damage_dealt[1] += game.damage_dealt[1];
+			result.games.push(game);
+		}
+
+		result
+	}
+}
+
+/// Plays `options.games` battles between the teams over a pool of threads
+pub fn simulate_batch(
+	teams: [&Team; 2],
+	agents: [&AgentFactory; 2],
+	status_map: &RegMap<StatusCondition>,
+	move_map: &RegMap<Move>,
+	rules: &Ruleset,
+	options: BatchOptions,
+) -> Result<BatchResult, BattleError>
+{
+	parallel_map(options.games as usize, options.threads, |game| {
+		play_game(
+			teams,
+			agents,
+			options.participants,
+			status_map,
+			move_map,
+			rules,
+			game_seed(options.seed, game),
+			options.max_turns,
+		)
+	})
+	.into_iter()
+	.collect()
+}
+
+/// The seed for game `game` of a run seeded with `seed`. Games are seeded three apart, one seed
+/// for the battle and one for each side's agent
+pub fn game_seed(seed: Option<u64>, game: usize) -> Option<u64>
+{
+	seed.map(|it| it.wrapping_add((game as u64).wrapping_mul(3)))
+}
+
+/// Runs `job` for every index below `count` on up to `threads` threads, returning the results in
+/// index order
+pub fn parallel_map<T, F>(count: usize, threads: usize, job: F) -> Vec<T>
+where
+	T: Send,
+	F: Fn(usize) -> T + Sync,
+{
+	let next = AtomicUsize::new(0);
+	let mut results = std::thread::scope(|scope| {
+		let workers = (0..threads.clamp(1, count.max(1)))
+			.map(|_| {
+				scope.spawn(|| {
+					let mut done = Vec::new();
+					loop
+					{
+						let index = next.fetch_add(1, Ordering::Relaxed);
+						if index >= count
+						{
+							break done;
+						}
+						done.push((index, job(index)));
+					}
+				})
+			})
+			.collect::<Vec<_>>();
+
+		workers
+			.into_iter()
+			.flat_map(|it| it.join().expect("a simulation thread panicked"))
+			.collect::<Vec<_>>()
+	});
+
+	results.sort_by_key(|it| it.0);
+	results.into_iter().map(|it| it.1).collect()
+}
+
+/// How many threads to use when none are asked for
+pub fn default_threads() -> usize
+{
+	std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{
+		battle::{Agent, RandomAgent},
+		data::Pokemon,
+		MOVE_MAP, RULESET, SPECIES_MAP, STATUS_MAP,
+	};
+
+	#[test]
+	fn plays_the_same_on_any_number_of_threads()
+	{
+		let team = Team::new(String::from("Sand")).add_member(
+			Pokemon::new(&SPECIES_MAP["garchomp"])
+				.set_level(50)
+				.add_moves(["dragon_claw", "stone_edge"].map(|it| &MOVE_MAP[it])),
+		);
+		let random: AgentFactory =
+			Box::new(|seed| Box::new(RandomAgent::new(seed)) as Box<dyn Agent>);
+		let simulate = |threads| {
+			let options = BatchOptions {
+				games: 20,
+				max_turns: 200,
+				participants: [1, 1],
+				seed: Some(7),
+				threads,
+			};
+			simulate_batch(
+				[&team, &team],
+				[&random, &random],
+				&STATUS_MAP,
+				&MOVE_MAP,
+				&RULESET,
+				options,
+			)
+			.unwrap()
+		};
+
+		let result = simulate(1);
+		assert_eq!(result, simulate(4));
+		assert!(result.games.iter().any(|it| *it != result.games[0]));
+	}
+
+	#[test]
+	fn seeds_every_game_apart()
+	{
+		let seeds = (0..100)
+			.flat_map(|game| {
+				let seed = game_seed(Some(u64::MAX - 10), game).unwrap();
+				(0..3).map(move |it| seed.wrapping_add(it))
+			})
+			.collect::<std::collections::HashSet<_>>();
+		assert_eq!(seeds.len(), 300);
+	}
+}
@@ -0,0 +1,142 @@
+use crate::{battle, data};
+
+use battle::{Battle, BattleError, BattleEvent, Position};
+use data::{BossRules, Move, RegMap};
+use std::sync::Arc;
+
+/// Where the boss of a noble battle stands. It's always side 2's only pokemon
+pub const BOSS_POSITION: Position = Position { side: 1, slot: 0 };
+
+/// The rules of a noble battle along with each phase's moves, looked up in the dex. Battles get
+/// cloned a lot while searching, so this is shared rather than copied
+#[derive(Clone)]
+pub(super) struct Boss<'a>
+{
+	pub(super) rules: Arc<BossRules>,
+	move_pools: Arc<[Box<[&'a Move<'a>]>]>,
+	/// The phase the boss is in, if it has reached one
+	pub(super) phase: Option<usize>,
+}
+
+impl<'a> Battle<'a>
+{
+	/// Turns the battle into a noble battle against the first pokemon of side 2's team, which is
+	/// left to fight alone with its stats scaled by the rules
+	pub fn with_boss(
+		mut self,
+		rules: BossRules,
+		move_map: &'a RegMap<Move<'a>>,
+	) -> Result<Self, BattleError>
+	{
+		let boss_side = &mut self.sides[BOSS_POSITION.side];
+		boss_side.party.truncate(1);
+		boss_side.active.truncate(1);
+		self.events.retain(|it| {
+			!matches!(it, BattleEvent::SentOut { position, .. }
+				if position.side == BOSS_POSITION.side && position.slot > 0)
+		});
+
+		self.set_boss(rules, None, move_map)?;
+		// The boss was given its action time before its speed was scaled
+		let boss = self.sides[BOSS_POSITION.side].active_mut(BOSS_POSITION.slot);
+		boss.set_action_time(boss.base_action_time());
+		self.update_boss_phase();
+		Ok(self)
+	}
+
+	/// The rules of the noble battle, or `None` if this isn't one
+	pub fn boss_rules(&self) -> Option<&BossRules>
+	{
+		self.boss.as_ref().map(|it| &*it.rules)
+	}
+
+	/// The phase the boss is in, counting from 0, or `None` if it hasn't reached one yet or
+	/// there's no boss
+	pub fn boss_phase(&self) -> Option<usize>
+	{
+		self.boss.as_ref().and_then(|it| it.phase)
+	}
+
+	/// Sets the boss up to fight by the rules from the given phase
+	pub(super) fn set_boss(
+		&mut self,
+		rules: BossRules,
+		phase: Option<usize>,
+		move_map: &'a RegMap<Move<'a>>,
+	) -> Result<(), BattleError>
+	{
+		let phase = phase.filter(|it| *it < rules.phases.len());
+		let move_pools = rules
+			.phases
+			.iter()
+			.map(|phase| {
+				phase
+					.moves
+					.iter()
+					.map(|id| move_map.get(id).ok_or_else(|| BattleError::MissingId(id.clone())))
+					.collect::<Result<Box<[_]>, _>>()
+			})
+			.collect::<Result<Arc<[_]>, _>>()?;
+
+		let boss = self.sides[BOSS_POSITION.side].active_mut(BOSS_POSITION.slot);
+		boss.scale_stats(rules.hp_multiplier, rules.stat_multiplier);
+		boss.set_move_pool(phase.map(|it| move_pools[it].clone()));
+		self.boss = Some(Boss {
+			rules: Arc::new(rules),
+			move_pools,
+			phase,
+		});
+		Ok(())
+	}
+
+	/// Moves the boss on to the latest phase its hp has fallen far enough for
+	pub(super) fn update_boss_phase(&mut self)
+	{
+		let Some(boss) = &mut self.boss
+		else
+		{
+			return;
+		};
+		let pokemon = self.sides[BOSS_POSITION.side].active_mut(BOSS_POSITION.slot);
+		let hp_percent = pokemon.current_hp() * 100 / pokemon.max_hp().max(1);
+		let reached = boss
+			.rules
+			.phases
+			.iter()
+			.rposition(|it| hp_percent <= it.hp_percent);
+		if reached <= boss.phase || pokemon.is_fainted()
+		{
+			return;
+		}
+
+		boss.phase = reached;
+		pokemon.set_move_pool(reached.map(|it| boss.move_pools[it].clone()));
+		if let Some(phase) = reached
+		{
+			self.events.push(BattleEvent::PhaseChanged {
+				position: BOSS_POSITION,
+				phase,
+			});
+		}
+	}
+
+	/// The winner by the noble battle's own rules, if they've decided it
+	pub(super) fn boss_winner(&self) -> Option<usize>
+	{
+		let rules = &self.boss.as_ref()?.rules;
+		let challenger = 1 - BOSS_POSITION.side;
+		if self.pokemon_at(BOSS_POSITION).is_fainted()
+		{
+			return Some(challenger);
+		}
+
+		let fainted = self.sides[challenger]
+			.party
+			.iter()
+			.filter(|it| it.is_fainted())
+			.count();
+		let out_of_pokemon = rules.faints_allowed.is_some_and(|it| fainted >= it);
+		let out_of_time = rules.turn_limit.is_some_and(|it| self.turn >= it);
+		(out_of_pokemon || out_of_time).then_some(BOSS_POSITION.side)
+	}
+}
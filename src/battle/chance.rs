@@ -0,0 +1,139 @@
+use crate::{analysis, battle, data};
+
+use battle::{Action, Battle, BattleError, Decision};
+use data::{Effect, MoveEffect, Style};
+
+/// How every roll of one action goes, so that each way an action can turn out can be looked at
+/// on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChanceOutcome
+{
+	/// Whether a status stops the actor from moving
+	pub cancelled: bool,
+	pub hit: bool,
+	pub crit: bool,
+	/// Whether statuses the move only has a chance of applying take hold. The first of a move's
+	/// status options is the one applied
+	pub status_applies: bool,
+	/// The damage roll, as a percentage of the unrolled damage
+	pub damage_roll: i32,
+}
+
+impl<'a> Battle<'a>
+{
+	/// Carries out the action with its rolls going the way `outcome` says, leaving the battle's
+	/// own generator untouched
+	pub fn apply_with(&mut self, action: Action<'a>, outcome: ChanceOutcome)
+		-> Result<(), BattleError>
+	{
+		self.forced = Some(outcome);
+		let result = self.apply(action);
+		self.forced = None;
+		result
+	}
+
+	/// Every way the action can turn out along with its chance, which add up to 1. Damage rolls
+	/// aren't branched on, every outcome uses the middle roll
+	pub fn chance_outcomes(&self, action: Action<'a>) -> Vec<(f64, ChanceOutcome)>
+	{
+		let rolls = &self.rules.damage_rolls;
+		let certain = ChanceOutcome {
+			cancelled: false,
+			hit: true,
+			crit: false,
+			status_applies: true,
+			damage_roll: (rolls.start + rolls.end - 1) / 2,
+		};
+		let position = match self.decision()
+		{
+			Decision::Action(it) => it,
+			Decision::Replacement(_) | Decision::Finished(_) => return vec![(1.0, certain)],
+		};
+
+		let actor = self.pokemon_at(position);
+		let cancel_chance = 1.0
+			- actor
+				.status_effects()
+				.filter_map(|eff| match eff
+				{
+					Effect::CancelTurn { chance } => Some(1.0 - f64::from(*chance) / 100.0),
+					_ => None,
+				})
+				.product::<f64>();
+		let mut outcomes = vec![(
+			cancel_chance,
+			ChanceOutcome {
+				cancelled: true,
+				..certain
+			},
+		)];
+
+		match action
+		{
+			Action::Switch(_) => outcomes.push((1.0 - cancel_chance, certain)),
+			Action::Move { mv, style, target } =>
+			{
+				// a spread move's rolls go the same way for every pokemon it hits, so the pokemon
+				// it's aimed at stands in for all of them
+				let target = self.pokemon_at(target);
+				let resolved = battle::resolve_move(actor, target, mv);
+				let hit = analysis::hit_chance(target, &resolved, style);
+				let crit = if resolved.category.is_damaging()
+				{
+					analysis::crit_chance(actor, &resolved, style)
+				}
+				else
+				{
+					0.0
+				};
+				let status = status_chance(&resolved, actor, target, style);
+
+				let go_ahead = 1.0 - cancel_chance;
+				outcomes.push((go_ahead * (1.0 - hit), ChanceOutcome { hit: false, ..certain }));
+				for (crit_chance, is_crit) in [(1.0 - crit, false), (crit, true)]
+				{
+					for (status_chance, applies) in [(1.0 - status, false), (status, true)]
+					{
+						outcomes.push((
+							go_ahead * hit * crit_chance * status_chance,
+							ChanceOutcome {
+								crit: is_crit,
+								status_applies: applies,
+								..certain
+							},
+						));
+					}
+				}
+			}
+		}
+
+		outcomes.retain(|it| it.0 > 0.0);
+		outcomes
+	}
+}
+
+/// The chance the move's first rolled status takes hold, or 0 if it doesn't try to apply any
+fn status_chance(
+	mv: &data::Move,
+	user: &data::BattlePokemon,
+	target: &data::BattlePokemon,
+	style: Style,
+) -> f64
+{
+	mv.effects
+		.iter()
+		.find_map(|effect| match effect
+		{
+			MoveEffect::ApplyStatus {
+				status_option_ids,
+				chance,
+				condition,
+				..
+			} if !status_option_ids.is_empty() && condition.is_met(user, target) =>
+			{
+				Some(f64::from(chance[style].clamp(0, 100)) / 100.0)
+			}
+			_ => None,
+		})
+		.unwrap_or_default()
+}
@@ -0,0 +1,141 @@
+use crate::{battle::Position, data::Style};
+
+/// Something that happened in a battle, in the order it happened
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "event")]
+pub enum BattleEvent
+{
+	SentOut
+	{
+		position: Position,
+		/// Position of the pokemon in its side's party
+		member: usize,
+		species: Box<str>,
+		is_alpha: bool,
+	},
+	UsedMove
+	{
+		user: Position,
+		target: Position,
+		move_id: Box<str>,
+		style: Style,
+	},
+	Missed
+	{
+		user: Position,
+		target: Position,
+	},
+	TurnCancelled
+	{
+		position: Position,
+		status_id: Box<str>,
+	},
+	Damaged
+	{
+		position: Position,
+		amount: i32,
+		is_crit: bool,
+		effectiveness: f64,
+	},
+	Recoil
+	{
+		position: Position, amount: i32
+	},
+	Healed
+	{
+		position: Position, amount: i32
+	},
+	StatusApplied
+	{
+		position: Position,
+		status_id: Box<str>,
+	},
+	StatusCured
+	{
+		position: Position,
+		status_id: Box<str>,
+	},
+	StatusDamage
+	{
+		position: Position,
+		status_id: Box<str>,
+		amount: i32,
+	},
+	Fainted
+	{
+		position: Position, member: usize
+	},
+	/// A noble pokemon moved on to another phase, counting from 0
+	PhaseChanged
+	{
+		position: Position, phase: usize
+	},
+	/// Sides are 0 and 1
+	Won
+	{
+		side: usize
+	},
+	Draw,
+}
+impl std::fmt::Display for BattleEvent
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			Self::SentOut {
+				position,
+				species,
+				is_alpha,
+				..
+			} =>
+			{
+				let alpha = if *is_alpha { "alpha " } else { "" };
+				write!(f, "{position} sent out {alpha}{species}")
+			}
+			Self::UsedMove {
+				user,
+				target,
+				move_id,
+				style,
+			} => write!(f, "{user} used {move_id} ({style:?}) on {target}"),
+			Self::Missed { user, target } => write!(f, "{user}'s attack missed {target}"),
+			Self::TurnCancelled {
+				position,
+				status_id,
+			} => write!(f, "{position} could not move because of {status_id}"),
+			Self::Damaged {
+				position,
+				amount,
+				is_crit,
+				effectiveness,
+			} =>
+			{
+				let crit = if *is_crit { ", a critical hit" } else { "" };
+				write!(f, "{position} took {amount} damage (x{effectiveness}{crit})")
+			}
+			Self::Recoil { position, amount } => write!(f, "{position} took {amount} recoil"),
+			Self::Healed { position, amount } => write!(f, "{position} healed {amount} hp"),
+			Self::StatusApplied {
+				position,
+				status_id,
+			} => write!(f, "{position} is afflicted with {status_id}"),
+			Self::StatusCured {
+				position,
+				status_id,
+			} => write!(f, "{position} is no longer afflicted with {status_id}"),
+			Self::StatusDamage {
+				position,
+				status_id,
+				amount,
+			} => write!(f, "{position} took {amount} damage from {status_id}"),
+			Self::Fainted { position, .. } => write!(f, "{position}'s pokemon fainted"),
+			Self::PhaseChanged { position, phase } =>
+			{
+				write!(f, "{position}'s pokemon entered phase {}", phase + 1)
+			}
+			Self::Won { side } => write!(f, "side {} won", side + 1),
+			Self::Draw => write!(f, "the battle is a draw"),
+		}
+	}
+}
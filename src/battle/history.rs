@@ -0,0 +1,317 @@
+use crate::battle;
+
+use battle::{Action, Agent, Battle, BattleError, BattleEvent, Decision};
+
+/// A battle along with a snapshot of it after every action, replacements included, so actions can
+/// be taken back and the battle picked up again from any earlier point. Snapshots leave out the
+/// event log, which is kept once for the whole history, so each one only costs as much as the two
+/// parties
+#[derive(Clone)]
+pub struct BattleHistory<'a>
+{
+	/// The battle after each action, starting with the battle the history was made from. Those
+	/// past `current` have been undone and can be redone
+	snapshots: Vec<Battle<'a>>,
+	/// The action leading to each snapshot after the first
+	actions: Vec<Action<'a>>,
+	/// How many events had happened by each snapshot
+	event_counts: Vec<usize>,
+	events: Vec<BattleEvent>,
+	current: usize,
+}
+impl<'a> BattleHistory<'a>
+{
+	pub fn new(mut battle: Battle<'a>) -> Self
+	{
+		let events = std::mem::take(&mut battle.events);
+		Self {
+			snapshots: vec![battle],
+			actions: Vec::new(),
+			event_counts: vec![events.len()],
+			events,
+			current: 0,
+		}
+	}
+
+	/// The battle as it stands. Its own event log is empty, the events so far are in
+	/// [`Self::events`]
+	pub fn battle(&self) -> &Battle<'a>
+	{
+		&self.snapshots[self.current]
+	}
+
+	/// Every event up to where the history stands
+	pub fn events(&self) -> &[BattleEvent]
+	{
+		&self.events[..self.event_counts[self.current]]
+	}
+
+	/// The actions taken since the history was made, leaving out undone ones
+	pub fn actions(&self) -> &[Action<'a>]
+	{
+		&self.actions[..self.current]
+	}
+
+	/// Takes the action where the history stands. Anything undone can't be redone after this
+	pub fn apply(&mut self, action: Action<'a>) -> Result<(), BattleError>
+	{
+		let mut next = battle::lookahead(self.battle());
+		next.apply(action)?;
+
+		self.snapshots.truncate(self.current + 1);
+		self.actions.truncate(self.current);
+		self.event_counts.truncate(self.current + 1);
+		self.events.truncate(self.event_counts[self.current]);
+
+		self.events.append(&mut next.events);
+		self.event_counts.push(self.events.len());
+		self.actions.push(action);
+		self.snapshots.push(next);
+		self.current += 1;
+		Ok(())
+	}
+
+	/// Lets the agents play from where the history stands, like [`Battle::run`]
+	pub fn run(
+		&mut self,
+		agents: &mut [&mut dyn Agent; 2],
+		max_turns: u32,
+	) -> Result<Option<usize>, BattleError>
+	{
+		loop
+		{
+			let battle = self.battle();
+			let side = match battle.decision()
+			{
+				Decision::Finished(winner) => return Ok(winner),
+				_ if battle.turn() >= max_turns => return Ok(None),
+				Decision::Action(it) | Decision::Replacement(it) => it.side,
+			};
+			let action = agents[side].choose(&battle.view(side));
+			self.apply(action)?;
+		}
+	}
+
+	pub fn can_undo(&self) -> bool
+	{
+		self.current > 0
+	}
+
+	pub fn can_redo(&self) -> bool
+	{
+		self.current + 1 < self.snapshots.len()
+	}
+
+	/// Steps back over the last action, returning it, or `None` if there's nothing to undo
+	pub fn undo(&mut self) -> Option<Action<'a>>
+	{
+		if !self.can_undo()
+		{
+			return None;
+		}
+
+		self.current -= 1;
+		Some(self.actions[self.current])
+	}
+
+	/// Takes the last undone action again, returning it, or `None` if there's nothing to redo
+	pub fn redo(&mut self) -> Option<Action<'a>>
+	{
+		if !self.can_redo()
+		{
+			return None;
+		}
+
+		self.current += 1;
+		Some(self.actions[self.current - 1])
+	}
+
+	/// The battle as it was right after its turn count reached `turn`, before any replacements
+	/// that followed, if the history has it. Undone turns count until something else is applied
+	/// in their place
+	pub fn at_turn(&self, turn: u32) -> Option<&Battle<'a>>
+	{
+		self.index_of(turn).map(|it| &self.snapshots[it])
+	}
+
+	/// A separate history standing at `turn`, to try something else from there while leaving
+	/// this one as it is. It keeps everything before `turn`, so it can be undone further back
+	pub fn branch(&self, turn: u32) -> Option<Self>
+	{
+		let index = self.index_of(turn)?;
+		Some(Self {
+			snapshots: self.snapshots[..=index].to_vec(),
+			actions: self.actions[..index].to_vec(),
+			event_counts: self.event_counts[..=index].to_vec(),
+			events: self.events[..self.event_counts[index]].to_vec(),
+			current: index,
+		})
+	}
+
+	/// The battle where the history stands along with its whole event log, to carry on with on
+	/// its own
+	pub fn to_battle(&self) -> Battle<'a>
+	{
+		let mut battle = self.battle().clone();
+		battle.events = self.events().to_vec();
+		battle
+	}
+
+	fn index_of(&self, turn: u32) -> Option<usize>
+	{
+		self.snapshots.iter().position(|it| it.turn() == turn)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{
+		battle::{RandomAgent, SerBattle},
+		data::{Pokemon, Team},
+		MOVE_MAP, RULESET, SPECIES_MAP, STATUS_MAP,
+	};
+
+	fn team(species: &str, moves: [&str; 2]) -> Team<'static>
+	{
+		Team::new(String::from(species)).add_member(
+			Pokemon::new(&SPECIES_MAP[species])
+				.set_level(50)
+				.add_moves(moves.map(|it| &MOVE_MAP[it])),
+		)
+	}
+
+	/// Everything about the battle but its event log, to compare battles with
+	fn state(battle: &Battle, teams: [&Team; 2]) -> String
+	{
+		let mut saved = SerBattle::new(battle, teams);
+		saved.events.clear();
+		serde_json::to_string(&saved).unwrap()
+	}
+
+	fn play(history: &mut BattleHistory, agent: &mut RandomAgent, actions: usize)
+	{
+		for _ in 0..actions
+		{
+			let battle = history.battle();
+			let side = match battle.decision()
+			{
+				Decision::Action(it) | Decision::Replacement(it) => it.side,
+				Decision::Finished(_) => panic!("the battle ended early"),
+			};
+			let action = agent.choose(&battle.view(side));
+			history.apply(action).unwrap();
+		}
+	}
+
+	#[test]
+	fn undoing_and_redoing_gives_back_the_same_battle()
+	{
+		let teams = [
+			&team("garchomp", ["dragon_claw", "stone_edge"]),
+			&team("snorlax", ["giga_impact", "rest"]),
+		];
+		let battle = Battle::new(teams, &STATUS_MAP, &MOVE_MAP, &RULESET, Some(3)).unwrap();
+		let mut history = BattleHistory::new(battle);
+		play(&mut history, &mut RandomAgent::new(Some(3)), 6);
+
+		let before = state(history.battle(), teams);
+		let events = history.events().to_vec();
+		let actions = history.actions().to_vec();
+
+		let undone: Vec<_> = (0..3).map_while(|_| history.undo()).collect();
+		assert_eq!(undone, actions[3..].iter().rev().copied().collect::<Vec<_>>());
+		assert!(history.events().len() < events.len());
+		assert_ne!(state(history.battle(), teams), before);
+
+		let redone: Vec<_> = (0..3).map_while(|_| history.redo()).collect();
+		assert_eq!(redone, actions[3..]);
+		assert_eq!(history.redo(), None);
+		assert_eq!(state(history.battle(), teams), before);
+		assert_eq!(history.events(), events);
+		assert_eq!(history.actions(), actions);
+	}
+
+	#[test]
+	fn applying_after_an_undo_drops_what_was_undone()
+	{
+		let teams = [
+			&team("garchomp", ["dragon_claw", "stone_edge"]),
+			&team("snorlax", ["giga_impact", "rest"]),
+		];
+		let battle = Battle::new(teams, &STATUS_MAP, &MOVE_MAP, &RULESET, Some(5)).unwrap();
+		let mut history = BattleHistory::new(battle);
+		let mut agent = RandomAgent::new(Some(5));
+		play(&mut history, &mut agent, 4);
+		let actions = history.actions().to_vec();
+
+		history.undo();
+		history.undo();
+		assert!(history.can_redo());
+		play(&mut history, &mut agent, 1);
+
+		assert!(!history.can_redo());
+		assert_eq!(history.redo(), None);
+		assert_eq!(history.actions().len(), 3);
+		assert_eq!(history.actions()[..2], actions[..2]);
+		assert_eq!(history.to_battle().events(), history.events());
+	}
+
+	#[test]
+	fn finds_each_turn_before_its_action()
+	{
+		let teams = [
+			&team("garchomp", ["dragon_claw", "stone_edge"]),
+			&team("snorlax", ["giga_impact", "rest"]),
+		];
+		let mut battle = Battle::new(teams, &STATUS_MAP, &MOVE_MAP, &RULESET, Some(9)).unwrap();
+		let mut history = BattleHistory::new(battle.clone());
+		let mut agent = RandomAgent::new(Some(9));
+		let mut before_turn = Vec::new();
+		for _ in 0..5
+		{
+			let Decision::Action(position) = battle.decision()
+			else
+			{
+				break;
+			};
+			before_turn.push((battle.turn(), state(&battle, teams)));
+			let action = agent.choose(&battle.view(position.side));
+			battle.apply(action).unwrap();
+			history.apply(action).unwrap();
+		}
+
+		assert_eq!(before_turn.len(), 5);
+		for (turn, expected) in &before_turn
+		{
+			let snapshot = history.at_turn(*turn).unwrap();
+			assert_eq!(snapshot.turn(), *turn);
+			assert_eq!(&state(snapshot, teams), expected);
+		}
+		assert!(history.at_turn(100).is_none());
+	}
+
+	#[test]
+	fn branches_leave_the_original_as_it_is()
+	{
+		let teams = [
+			&team("garchomp", ["dragon_claw", "stone_edge"]),
+			&team("snorlax", ["giga_impact", "rest"]),
+		];
+		let battle = Battle::new(teams, &STATUS_MAP, &MOVE_MAP, &RULESET, Some(11)).unwrap();
+		let mut history = BattleHistory::new(battle);
+		play(&mut history, &mut RandomAgent::new(Some(11)), 4);
+		let before = state(history.battle(), teams);
+		let actions = history.actions().to_vec();
+
+		let mut branch = history.branch(2).unwrap();
+		assert_eq!(branch.battle().turn(), 2);
+		assert_eq!(branch.actions(), &actions[..2]);
+		assert!(!branch.can_redo());
+		play(&mut branch, &mut RandomAgent::new(Some(12)), 2);
+
+		assert_eq!(state(history.battle(), teams), before);
+		assert_eq!(history.actions(), actions);
+	}
+}
@@ -0,0 +1,296 @@
+use crate::battle;
+
+use battle::{Action, Agent, Battle, BattleView, Decision, Evaluation, Position};
+use rand::{rngs::StdRng, seq::SliceRandom, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::time::{Duration, Instant};
+
+/// How much searching the agent does before deciding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget
+{
+	Iterations(u32),
+	Time(Duration),
+}
+
+/// What the search found out about one action
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionStats<'a>
+{
+	pub action: Action<'a>,
+	pub visits: u32,
+	/// Average result from -1 for a loss to 1 for a win
+	pub value: f64,
+}
+
+/// Monte Carlo tree search. Each iteration plays the battle forward with its own seeded rolls,
+/// picking actions by UCB1 while they've been tried before and at random past that, and scores
+/// where it ends up with the evaluation. The most visited action is the one taken. Like
+/// [`battle::ExpectiminimaxAgent`], it sees both parties in full
+pub struct MctsAgent<E = fn(&Battle, usize) -> f64>
+{
+	pub budget: Budget,
+	/// How strongly less visited actions are preferred
+	pub exploration: f64,
+	/// How many random actions a rollout takes before it's scored
+	pub rollout_depth: u32,
+	evaluation: E,
+	rng: StdRng,
+}
+impl MctsAgent
+{
+	pub const DEFAULT_ITERATIONS: u32 = 1000;
+
+	pub fn new(budget: Budget, seed: Option<u64>) -> Self
+	{
+		Self {
+			budget,
+			exploration: std::f64::consts::SQRT_2,
+			rollout_depth: 40,
+			evaluation: battle::evaluate,
+			rng: seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+		}
+	}
+}
+impl<E: Evaluation> MctsAgent<E>
+{
+	/// The same agent scoring battles with something else
+	pub fn with_evaluation<F: Evaluation>(self, evaluation: F) -> MctsAgent<F>
+	{
+		MctsAgent {
+			budget: self.budget,
+			exploration: self.exploration,
+			rollout_depth: self.rollout_depth,
+			evaluation,
+			rng: self.rng,
+		}
+	}
+
+	/// Searches from the battle as it is for the side it's waiting on, returning every action
+	/// that side can take, most visited first. Empty if the battle is over
+	pub fn analyze<'a>(&mut self, battle: &Battle<'a>) -> Vec<ActionStats<'a>>
+	{
+		let position = match battle.decision()
+		{
+			Decision::Action(it) | Decision::Replacement(it) => it,
+			Decision::Finished(_) => return Vec::new(),
+		};
+		let side = position.side;
+
+		let mut root = Node::default();
+		let start = Instant::now();
+		let mut iterations = 0;
+		while match self.budget
+		{
+			Budget::Iterations(max) => iterations < max.max(1),
+			Budget::Time(limit) => iterations == 0 || start.elapsed() < limit,
+		}
+		{
+			let mut sample = battle::lookahead(battle);
+			sample.rng = ChaCha12Rng::seed_from_u64(self.rng.next_u64());
+			let value = self.simulate(&mut root, &mut sample, side);
+			root.record(value);
+			iterations += 1;
+		}
+
+		let mut stats = battle
+			.legal_actions(side)
+			.into_iter()
+			.map(|action| {
+				let child = root.child(position, action);
+				ActionStats {
+					action,
+					visits: child.map_or(0, |it| it.visits),
+					value: child.map_or(0.0, Node::mean),
+				}
+			})
+			.collect::<Vec<_>>();
+		stats.sort_by(|a, b| b.visits.cmp(&a.visits).then(b.value.total_cmp(&a.value)));
+		stats
+	}
+
+	/// Plays one iteration down from the node, returning its result for `side`
+	fn simulate<'a>(&mut self, node: &mut Node<'a>, battle: &mut Battle<'a>, side: usize) -> f64
+	{
+		let actor = match battle.decision()
+		{
+			Decision::Action(actor) | Decision::Replacement(actor) => actor,
+			Decision::Finished(_) => return self.score(battle, side),
+		};
+
+		let actions = battle.legal_actions(actor.side);
+		let untried = actions
+			.iter()
+			.filter(|it| node.child(actor, **it).is_none())
+			.copied()
+			.collect::<Vec<_>>();
+		if let Some(action) = untried.choose(&mut self.rng).copied()
+		{
+			battle.apply(action).expect("only legal actions are tried");
+			let value = self.rollout(battle, side);
+			node.children.push(((actor, action), Node::default()));
+			node.children.last_mut().expect("just pushed").1.record(value);
+			return value;
+		}
+
+		// every action has been tried, so go down the one with the best upper confidence bound,
+		// seen from whoever is acting
+		let parent_visits = f64::from(
+			actions
+				.iter()
+				.filter_map(|it| node.child(actor, *it))
+				.map(|it| it.visits)
+				.sum::<u32>(),
+		);
+		let sign = if actor.side == side { 1.0 } else { -1.0 };
+		let exploration = self.exploration;
+		let action = battle::agent::best_by(&actions, |it| {
+			let child = node.child(actor, *it).expect("every action has been tried");
+			sign * child.mean()
+				+ exploration * (parent_visits.ln() / f64::from(child.visits)).sqrt()
+		});
+
+		battle.apply(action).expect("only legal actions are tried");
+		let child = node.child_mut(actor, action).expect("every action has been tried");
+		let value = self.simulate(child, battle, side);
+		child.record(value);
+		value
+	}
+
+	/// Random actions from here until the battle ends or the rollout runs out of actions
+	fn rollout(&mut self, battle: &mut Battle, side: usize) -> f64
+	{
+		for _ in 0..self.rollout_depth
+		{
+			let actor = match battle.decision()
+			{
+				Decision::Action(actor) | Decision::Replacement(actor) => actor.side,
+				Decision::Finished(_) => break,
+			};
+			let action = *battle
+				.legal_actions(actor)
+				.choose(&mut self.rng)
+				.expect("a battle that isn't over always has an action");
+			battle.apply(action).expect("only legal actions are tried");
+		}
+
+		self.score(battle, side)
+	}
+
+	/// The evaluation squashed into -1 to 1, so that wins and losses sit at the ends
+	fn score(&self, battle: &Battle, side: usize) -> f64
+	{
+		self.evaluation.evaluate(battle, side).tanh()
+	}
+}
+impl<E: Evaluation> Agent for MctsAgent<E>
+{
+	fn choose<'a>(&mut self, view: &BattleView<'_, 'a>) -> Action<'a>
+	{
+		self.analyze(view.battle)
+			.first()
+			.map_or(view.actions[0], |it| it.action)
+	}
+}
+
+/// Search statistics for the actions taken so far in an iteration. Rolls aren't part of the
+/// tree, so the same node stands for every way they could have gone
+#[derive(Default)]
+struct Node<'a>
+{
+	visits: u32,
+	/// Sum of results for the searching side
+	total: f64,
+	/// Keyed by the acting pokemon and its action, since rolls can change who acts next
+	children: Vec<((Position, Action<'a>), Self)>,
+}
+impl<'a> Node<'a>
+{
+	fn record(&mut self, value: f64)
+	{
+		self.visits += 1;
+		self.total += value;
+	}
+
+	fn mean(&self) -> f64
+	{
+		self.total / f64::from(self.visits.max(1))
+	}
+
+	fn child(&self, actor: Position, action: Action<'a>) -> Option<&Self>
+	{
+		self.children
+			.iter()
+			.find(|it| it.0 == (actor, action))
+			.map(|it| &it.1)
+	}
+
+	fn child_mut(&mut self, actor: Position, action: Action<'a>) -> Option<&mut Self>
+	{
+		self.children
+			.iter_mut()
+			.find(|it| it.0 == (actor, action))
+			.map(|it| &mut it.1)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{
+		data::{Pokemon, Team},
+		MOVE_MAP, RULESET, SPECIES_MAP, STATUS_MAP,
+	};
+
+	fn team(species: &str, level: u8, moves: [&str; 2]) -> Team<'static>
+	{
+		Team::new(String::from(species)).add_member(
+			Pokemon::new(&SPECIES_MAP[species])
+				.set_level(level)
+				.add_moves(moves.map(|it| &MOVE_MAP[it])),
+		)
+	}
+
+	#[test]
+	fn searches_the_same_for_the_same_seed()
+	{
+		let teams = [
+			team("garchomp", 50, ["dragon_claw", "stone_edge"]),
+			team("snorlax", 50, ["giga_impact", "rest"]),
+		];
+		let battle =
+			Battle::new([&teams[0], &teams[1]], &STATUS_MAP, &MOVE_MAP, &RULESET, Some(2)).unwrap();
+
+		let analyze = |seed| MctsAgent::new(Budget::Iterations(300), Some(seed)).analyze(&battle);
+		let stats = analyze(8);
+		assert_eq!(stats, analyze(8));
+		assert_eq!(stats.iter().map(|it| it.visits).sum::<u32>(), 300);
+
+		let choose =
+			|seed| MctsAgent::new(Budget::Iterations(300), Some(seed)).choose(&battle.view(0));
+		assert_eq!(choose(8), choose(8));
+		assert_eq!(choose(8), stats[0].action);
+	}
+
+	#[test]
+	fn takes_the_move_that_wins_on_the_spot()
+	{
+		let teams = [
+			team("garchomp", 100, ["splash", "dragon_claw"]),
+			team("snorlax", 10, ["giga_impact", "rest"]),
+		];
+		let battle =
+			Battle::new([&teams[0], &teams[1]], &STATUS_MAP, &MOVE_MAP, &RULESET, Some(2)).unwrap();
+		assert_eq!(battle.decision(), Decision::Action(Position::new(0, 0)));
+
+		let stats = MctsAgent::new(Budget::Iterations(300), Some(1)).analyze(&battle);
+		let Action::Move { mv, .. } = stats[0].action
+		else
+		{
+			panic!("the best action should be a move");
+		};
+		assert_eq!(&*mv.id, "dragon_claw");
+		assert!(stats[0].value > 0.9);
+	}
+}
@@ -0,0 +1,144 @@
+const INITIAL_RATING: f64 = 1500.0;
+
+/// How a game went for a player: 1 for a win, 0.5 for a draw, 0 for a loss
+pub type Score = f64;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct Elo
+{
+	pub rating: f64,
+}
+impl Elo
+{
+	/// How far a single game can move a rating
+	pub const K_FACTOR: f64 = 32.0;
+
+	/// Updates both ratings after one game, where `score` is how it went for `self`
+	pub fn update(&mut self, other: &mut Self, score: Score)
+	{
+		let expected = expected_score(self.rating, other.rating, 1.0);
+		let change = Self::K_FACTOR * (score - expected);
+		self.rating += change;
+		other.rating -= change;
+	}
+}
+impl Default for Elo
+{
+	fn default() -> Self
+	{
+		Self {
+			rating: INITIAL_RATING,
+		}
+	}
+}
+
+/// A Glicko rating, where the rating deviation shrinks the more games a player has played
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct Glicko
+{
+	pub rating: f64,
+	pub deviation: f64,
+}
+impl Glicko
+{
+	pub const INITIAL_DEVIATION: f64 = 350.0;
+	const Q: f64 = std::f64::consts::LN_10 / 400.0;
+
+	/// The rating after a rating period in which these games were played, given each opponent's
+	/// rating going into the period and how the game went for `self`
+	pub fn updated(&self, games: &[(Self, Score)]) -> Self
+	{
+		if games.is_empty()
+		{
+			return *self;
+		}
+
+		let mut variance_inverse = 0.0;
+		let mut improvement = 0.0;
+		for (opponent, score) in games
+		{
+			let weight = opponent.weight();
+			let expected = expected_score(self.rating, opponent.rating, weight);
+			variance_inverse += Self::Q.powi(2) * weight.powi(2) * expected * (1.0 - expected);
+			improvement += weight * (score - expected);
+		}
+
+		let precision = self.deviation.powi(-2) + variance_inverse;
+		Self {
+			rating: self.rating + Self::Q / precision * improvement,
+			deviation: precision.recip().sqrt(),
+		}
+	}
+
+	/// How much a game against this player counts, lower the less sure its rating is
+	fn weight(&self) -> f64
+	{
+		let pi = std::f64::consts::PI;
+		(1.0 + 3.0 * Self::Q.powi(2) * self.deviation.powi(2) / pi.powi(2))
+			.sqrt()
+			.recip()
+	}
+}
+impl Default for Glicko
+{
+	fn default() -> Self
+	{
+		Self {
+			rating: INITIAL_RATING,
+			deviation: Self::INITIAL_DEVIATION,
+		}
+	}
+}
+
+fn expected_score(rating: f64, opponent: f64, weight: f64) -> f64
+{
+	1.0 / (1.0 + 10f64.powf(-weight * (rating - opponent) / 400.0))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	#[test]
+	fn matches_the_worked_glicko_example()
+	{
+		// the example from Glickman's paper describing the Glicko system
+		let player = Glicko {
+			rating: 1500.0,
+			deviation: 200.0,
+		};
+		let opponent = |rating, deviation| Glicko { rating, deviation };
+		let updated = player.updated(&[
+			(opponent(1400.0, 30.0), 1.0),
+			(opponent(1550.0, 100.0), 0.0),
+			(opponent(1700.0, 300.0), 0.0),
+		]);
+
+		assert!((updated.rating - 1464.1).abs() < 0.1, "{updated:?}");
+		assert!((updated.deviation - 151.4).abs() < 0.1, "{updated:?}");
+		assert_eq!(player.updated(&[]), player);
+	}
+
+	#[test]
+	fn moves_elo_by_how_unexpected_the_result_was()
+	{
+		let mut first = Elo::default();
+		let mut second = Elo::default();
+		first.update(&mut second, 1.0);
+		assert!((first.rating - 1516.0).abs() < 1e-9);
+		assert!((second.rating - 1484.0).abs() < 1e-9);
+
+		let mut favourite = Elo { rating: 1700.0 };
+		let mut underdog = Elo { rating: 1500.0 };
+		favourite.update(&mut underdog, 0.0);
+		let change = 32.0 / (1.0 + 10f64.powf(-0.5));
+		assert!((favourite.rating - (1700.0 - change)).abs() < 1e-9);
+		assert!((underdog.rating - (1500.0 + change)).abs() < 1e-9);
+		assert!((favourite.rating - 1675.69).abs() < 0.01);
+
+		let mut drawn = Elo::default();
+		drawn.update(&mut Elo::default(), 0.5);
+		assert!((drawn.rating - INITIAL_RATING).abs() < 1e-9);
+	}
+}
@@ -0,0 +1,272 @@
+use crate::{battle, data};
+
+use battle::{Battle, BattleError, BattleEvent, BattleSide};
+use data::{
+	serialization::{MissingIdError, SerBattlePokemon, SerTeam},
+	BossRules, Move, RegMap, Ruleset, Species, StatusCondition, Team,
+};
+use rand_chacha::ChaCha12Rng;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SerBattleSide
+{
+	/// The first members of the side's team, which is all of them unless it's a noble
+	pub party: Box<[SerBattlePokemon]>,
+	/// The party member in each slot
+	pub active: Box<[usize]>,
+}
+impl SerBattleSide
+{
+	fn into_battle_side<'a>(
+		self,
+		side: usize,
+		team: &'a Team<'a>,
+		status_map: &'a RegMap<StatusCondition<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+		rules: &'a Ruleset,
+	) -> Result<BattleSide<'a>, BattleError>
+	{
+		if self.party.len() > team.members.len()
+			|| self.active.is_empty()
+			|| self.active.iter().any(|it| *it >= self.party.len())
+		{
+			return Err(BattleError::PartyMismatch(side));
+		}
+
+		Ok(BattleSide {
+			party: self
+				.party
+				.into_vec()
+				.into_iter()
+				.zip(&team.members)
+				.map(|(it, pokemon)| it.into_battle_pokemon(pokemon, rules, status_map, move_map))
+				.collect::<Result<_, _>>()?,
+			active: self.active.into_vec(),
+		})
+	}
+}
+
+/// A battle partway through, with everything in it named by dex id so it can be picked back up
+/// against the same data. The teams and ruleset are saved along with it, and the generator is saved
+/// where it stands, so a resumed battle plays out exactly as the original would have
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SerBattle
+{
+	pub teams: [SerTeam; 2],
+	pub rules: Ruleset,
+	pub sides: [SerBattleSide; 2],
+	pub rng: ChaCha12Rng,
+	pub time: i32,
+	pub turn: u32,
+	#[serde(default)]
+	pub events: Vec<BattleEvent>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub boss: Option<SerBoss>,
+}
+impl SerBattle
+{
+	/// Saves the battle along with the teams it was started with
+	pub fn new(battle: &Battle, teams: [&Team; 2]) -> Self
+	{
+		Self {
+			teams: teams.map(SerTeam::from),
+			rules: battle.rules.clone(),
+			sides: std::array::from_fn(|side| {
+				let battle_side = &battle.sides[side];
+				SerBattleSide {
+					party: battle_side.party.iter().map(SerBattlePokemon::from).collect(),
+					active: battle_side.active.clone().into_boxed_slice(),
+				}
+			}),
+			rng: battle.rng.clone(),
+			time: battle.time,
+			turn: battle.turn,
+			events: battle.events.clone(),
+			boss: battle.boss.as_ref().map(|it| SerBoss {
+				rules: BossRules::clone(&it.rules),
+				phase: it.phase,
+			}),
+		}
+	}
+
+	/// The saved teams, to load the battle with
+	pub fn teams<'a>(
+		&self,
+		species_map: &'a RegMap<Species<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+	) -> Result<[Team<'a>; 2], BattleError>
+	{
+		let [first, second] = self.teams.clone();
+		Ok([
+			first.into_team(species_map, move_map)?,
+			second.into_team(species_map, move_map)?,
+		])
+	}
+
+	/// Picks the battle back up with `teams` and `rules`, which should be the ones from
+	/// [`Self::teams`] and [`Self::rules`]
+	pub fn into_battle<'a>(
+		self,
+		teams: [&'a Team<'a>; 2],
+		status_map: &'a RegMap<StatusCondition<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+		rules: &'a Ruleset,
+	) -> Result<Battle<'a>, BattleError>
+	{
+		let [first, second] = self.sides;
+		let sides = [
+			first.into_battle_side(0, teams[0], status_map, move_map, rules)?,
+			second.into_battle_side(1, teams[1], status_map, move_map, rules)?,
+		];
+
+		let mut battle = Battle {
+			sides,
+			status_map,
+			struggle: battle::struggle(move_map)?,
+			rules,
+			rng: self.rng,
+			time: self.time,
+			turn: self.turn,
+			events: self.events,
+			forced: None,
+			boss: None,
+		};
+		if let Some(boss) = self.boss
+		{
+			battle.set_boss(boss.rules, boss.phase, move_map)?;
+		}
+
+		Ok(battle)
+	}
+}
+
+/// A noble battle's rules and how far into them it got
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SerBoss
+{
+	pub rules: BossRules,
+	pub phase: Option<usize>,
+}
+
+impl From<MissingIdError> for BattleError
+{
+	fn from(value: MissingIdError) -> Self
+	{
+		Self::MissingId(value.0)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{
+		battle::{Action, Agent, Decision, Position, RandomAgent, BOSS_POSITION},
+		data::{BossPhase, Pokemon},
+		MOVE_MAP, RULESET, SPECIES_MAP, STATUS_MAP,
+	};
+
+	fn statuses(battle: &Battle, position: Position) -> Vec<(String, i32, String)>
+	{
+		let mut statuses: Vec<_> = battle
+			.pokemon_at(position)
+			.status_conditions()
+			.map(|it| (it.condition.id.to_string(), it.duration, it.source_move.id.to_string()))
+			.collect();
+		statuses.sort();
+		statuses
+	}
+
+	fn state(battle: &Battle, teams: [&Team; 2]) -> String
+	{
+		serde_json::to_string(&SerBattle::new(battle, teams)).unwrap()
+	}
+
+	#[test]
+	fn resumes_a_noble_battle_exactly_where_it_left_off()
+	{
+		let challenger = Team::new(String::from("Challenger")).add_member(
+			Pokemon::new(&SPECIES_MAP["garchomp"])
+				.set_level(50)
+				.add_moves(["dragon_claw", "stone_edge"].map(|it| &MOVE_MAP[it])),
+		);
+		let noble = Team::new(String::from("Noble"))
+			.add_member(Pokemon::new(&SPECIES_MAP["kleavor"]).set_level(50));
+		let teams = [&challenger, &noble];
+		let rules = Ruleset {
+			stab_multiplier: 2.0,
+			crit_multiplier: 2.0,
+			..RULESET.clone()
+		};
+		let phase = |hp_percent, moves: &[&str]| BossPhase {
+			hp_percent,
+			moves: moves.iter().map(|it| Box::from(*it)).collect(),
+		};
+		let boss_rules = BossRules {
+			hp_multiplier: 3.0,
+			stat_multiplier: 1.1,
+			phases: Box::new([
+				phase(100, &["x-scissor", "air_slash"]),
+				phase(60, &["stone_axe", "close_combat"]),
+				phase(25, &["night_slash"]),
+			]),
+			faints_allowed: None,
+			turn_limit: Some(60),
+		};
+
+		let mut battle = Battle::new(teams, &STATUS_MAP, &MOVE_MAP, &rules, Some(4))
+			.and_then(|it| it.with_boss(boss_rules, &MOVE_MAP))
+			.unwrap();
+		let mut agents = [RandomAgent::new(Some(4)), RandomAgent::new(Some(5))];
+		let [first, second] = &mut agents;
+		battle.run(&mut [first, second], 3).unwrap();
+		let boss = battle.sides[BOSS_POSITION.side].active_mut(BOSS_POSITION.slot);
+		boss.take_damage(boss.current_hp() - boss.max_hp() / 2);
+		boss.apply_status(&STATUS_MAP["poison"], 3, &MOVE_MAP["stone_edge"]);
+		battle.update_boss_phase();
+		battle.sides[0].active_mut(0).apply_status(
+			&STATUS_MAP["paralysis"],
+			2,
+			&MOVE_MAP["stone_axe"],
+		);
+		assert_eq!(battle.boss_phase(), Some(1));
+
+		let saved = serde_json::to_string(&SerBattle::new(&battle, teams)).unwrap();
+		let loaded: SerBattle = serde_json::from_str(&saved).unwrap();
+		let loaded_rules = loaded.rules.clone();
+		let loaded_teams = loaded.teams(&SPECIES_MAP, &MOVE_MAP).unwrap();
+		let [first_team, second_team] = &loaded_teams;
+		let mut resumed = loaded
+			.into_battle([first_team, second_team], &STATUS_MAP, &MOVE_MAP, &loaded_rules)
+			.unwrap();
+
+		assert!((resumed.rules.stab_multiplier - 2.0).abs() < 1e-9);
+		assert_eq!(resumed.boss_phase(), Some(1));
+		assert_eq!(resumed.turn(), battle.turn());
+		assert_eq!(resumed.rng, battle.rng);
+		for position in [Position::new(0, 0), BOSS_POSITION]
+		{
+			let pokemon = battle.pokemon_at(position);
+			let resumed_pokemon = resumed.pokemon_at(position);
+			assert_eq!(resumed_pokemon.current_hp(), pokemon.current_hp());
+			assert_eq!(resumed_pokemon.max_hp(), pokemon.max_hp());
+			assert_eq!(resumed_pokemon.action_time(), pokemon.action_time());
+			assert_eq!(statuses(&resumed, position), statuses(&battle, position));
+		}
+		assert_eq!(
+			resumed.legal_actions(BOSS_POSITION.side),
+			battle.legal_actions(BOSS_POSITION.side)
+		);
+
+		let Decision::Action(actor) = battle.decision()
+		else
+		{
+			panic!("the battle should still be going");
+		};
+		let action: Action = RandomAgent::new(Some(6)).choose(&battle.view(actor.side));
+		battle.apply(action).unwrap();
+		resumed.apply(action).unwrap();
+		assert_eq!(resumed.events(), battle.events());
+		assert_eq!(state(&resumed, teams), state(&battle, teams));
+	}
+}
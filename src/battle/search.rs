@@ -0,0 +1,146 @@
+use crate::battle;
+
+use battle::{Action, Agent, Battle, BattleView, Decision};
+
+/// Score for a won battle, well above anything hp and statuses can add up to
+const WIN_SCORE: f64 = 1000.0;
+/// How much a status on a pokemon counts for or against its side, in whole health bars
+const STATUS_WEIGHT: f64 = 0.1;
+
+/// Looks `depth` actions ahead, counting actions from both sides. It takes the best action for
+/// its own side and the worst for the other at each turn, wherever the action time queue puts
+/// them, and averages over how accuracy, crit and status rolls can go. This lets it see things
+/// like an agile move buying a second turn before the opponent can act
+pub struct ExpectiminimaxAgent
+{
+	pub depth: u32,
+}
+impl ExpectiminimaxAgent
+{
+	pub const DEFAULT_DEPTH: u32 = 2;
+
+	pub fn new(depth: u32) -> Self
+	{
+		Self { depth }
+	}
+
+	fn value(&self, battle: &Battle, side: usize, depth: u32) -> f64
+	{
+		let actor = match battle.decision()
+		{
+			Decision::Action(actor) | Decision::Replacement(actor) if depth > 0 => actor.side,
+			_ => return evaluate(battle, side),
+		};
+
+		let values = battle
+			.legal_actions(actor)
+			.into_iter()
+			.map(|action| self.expected_value(battle, action, side, depth));
+		if actor == side
+		{
+			values.fold(f64::NEG_INFINITY, f64::max)
+		}
+		else
+		{
+			values.fold(f64::INFINITY, f64::min)
+		}
+	}
+
+	/// The value of the action averaged over how its rolls can go
+	fn expected_value<'a>(&self, battle: &Battle<'a>, action: Action<'a>, side: usize, depth: u32)
+		-> f64
+	{
+		battle
+			.chance_outcomes(action)
+			.into_iter()
+			.map(|(chance, outcome)| {
+				let mut next = lookahead(battle);
+				next.apply_with(action, outcome)
+					.expect("only legal actions are searched");
+				chance * self.value(&next, side, depth - 1)
+			})
+			.sum()
+	}
+}
+impl Default for ExpectiminimaxAgent
+{
+	fn default() -> Self
+	{
+		Self::new(Self::DEFAULT_DEPTH)
+	}
+}
+impl Agent for ExpectiminimaxAgent
+{
+	fn choose<'a>(&mut self, view: &BattleView<'_, 'a>) -> Action<'a>
+	{
+		let depth = self.depth.max(1);
+		battle::agent::best_by(&view.actions, |action| {
+			self.expected_value(view.battle, *action, view.side, depth)
+		})
+	}
+}
+
+/// Scores how good a battle looks for one side, higher being better. Anything with the same
+/// signature as [`evaluate`] works
+pub trait Evaluation
+{
+	fn evaluate(&self, battle: &Battle, side: usize) -> f64;
+}
+impl<F> Evaluation for F
+where
+	F: Fn(&Battle, usize) -> f64,
+{
+	fn evaluate(&self, battle: &Battle, side: usize) -> f64
+	{
+		self(battle, side)
+	}
+}
+
+/// How good the battle looks for `side`: its remaining hp as a fraction of each party member's
+/// max hp, plus a bonus for each status that helps and less a penalty for each that hurts, against
+/// the same for the other side
+pub fn evaluate(battle: &Battle, side: usize) -> f64
+{
+	match battle.decision()
+	{
+		Decision::Finished(Some(winner)) if winner == side => return WIN_SCORE,
+		Decision::Finished(Some(_)) => return -WIN_SCORE,
+		Decision::Finished(None) => return 0.0,
+		Decision::Action(_) | Decision::Replacement(_) => (),
+	}
+
+	let standing = |it: usize| {
+		battle
+			.side(it)
+			.party
+			.iter()
+			.filter(|pokemon| !pokemon.is_fainted())
+			.map(|pokemon| {
+				let statuses = pokemon
+					.status_conditions()
+					.map(|it| it.condition.helpfulness())
+					.sum::<i32>();
+				f64::from(pokemon.current_hp()) / f64::from(pokemon.max_hp())
+					+ STATUS_WEIGHT * f64::from(statuses)
+			})
+			.sum::<f64>()
+	};
+	standing(side) - standing(1 - side)
+}
+
+/// A copy of the battle to look ahead with, leaving out the event log
+pub(super) fn lookahead<'a>(battle: &Battle<'a>) -> Battle<'a>
+{
+	Battle {
+		sides: battle.sides.clone(),
+		status_map: battle.status_map,
+		struggle: battle.struggle,
+		rules: battle.rules,
+		rng: battle.rng.clone(),
+		time: battle.time,
+		turn: battle.turn,
+		events: Vec::new(),
+		forced: None,
+		boss: battle.boss.clone(),
+	}
+}
@@ -0,0 +1,280 @@
+use crate::{battle, data};
+
+use battle::{Agent, BattleError, Elo, Glicko, Score};
+use data::{Move, RegMap, Ruleset, StatusCondition, Team};
+
+/// Makes a fresh agent for each game, seeded for that game. Games are played on several threads
+/// at once, each making its own agents
+pub type AgentFactory = Box<dyn Fn(Option<u64>) -> Box<dyn Agent> + Send + Sync>;
+
+/// An agent playing a team
+pub struct Entrant<'a>
+{
+	pub name: String,
+	pub team: &'a Team<'a>,
+	pub agent: AgentFactory,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TournamentOptions
+{
+	/// Games played between every pair of entrants, taking turns on which side each is on
+	pub games: u32,
+	/// Actions after which a game counts as a draw
+	pub max_turns: u32,
+	/// How many pokemon both sides have out at once
+	pub participants: usize,
+	/// Seeds every battle and agent, so the same options always play out the same
+	pub seed: Option<u64>,
+	/// Worker threads to play games on. Results don't depend on it
+	pub threads: usize,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Standing
+{
+	pub name: String,
+	pub games: u32,
+	pub wins: u32,
+	pub losses: u32,
+	pub draws: u32,
+	pub elo: Elo,
+	pub glicko: Glicko,
+}
+
+/// How the games between two entrants went
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MatchupStats
+{
+	pub first: String,
+	pub second: String,
+	pub games: u32,
+	pub first_wins: u32,
+	pub second_wins: u32,
+	pub draws: u32,
+	pub total_turns: u64,
+	/// Damage from moves dealt to the other entrant's pokemon, across every game
+	pub first_damage_dealt: u64,
+	pub second_damage_dealt: u64,
+}
+impl MatchupStats
+{
+	pub fn average_turns(&self) -> f64
+	{
+		self.total_turns as f64 / f64::from(self.games.max(1))
+	}
+
+	pub fn average_damage_dealt(&self) -> (f64, f64)
+	{
+		let games = f64::from(self.games.max(1));
+		(
+			self.first_damage_dealt as f64 / games,
+			self.second_damage_dealt as f64 / games,
+		)
+	}
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TournamentResult
+{
+	/// Highest Elo first
+	pub standings: Vec<Standing>,
+	pub matchups: Vec<MatchupStats>,
+}
+
+/// Plays every pair of entrants against each other. Elo is updated after every game, and Glicko
+/// after every round, a round being one game between each pair
+pub fn run_tournament(
+	entrants: &[Entrant],
+	status_map: &RegMap<StatusCondition>,
+	move_map: &RegMap<Move>,
+	rules: &Ruleset,
+	options: TournamentOptions,
+) -> Result<TournamentResult, BattleError>
+{
+	let mut standings = entrants
+		.iter()
+		.map(|it| Standing {
+			name: it.name.clone(),
+			games: 0,
+			wins: 0,
+			losses: 0,
+			draws: 0,
+			elo: Elo::default(),
+			glicko: Glicko::default(),
+		})
+		.collect::<Vec<_>>();
+	let pairs = (0..entrants.len())
+		.flat_map(|first| (first + 1..entrants.len()).map(move |second| (first, second)))
+		.collect::<Vec<_>>();
+	let mut matchups = pairs
+		.iter()
+		.map(|(first, second)| MatchupStats {
+			first: entrants[*first].name.clone(),
+			second: entrants[*second].name.clone(),
+			games: 0,
+			first_wins: 0,
+			second_wins: 0,
+			draws: 0,
+			total_turns: 0,
+			first_damage_dealt: 0,
+			second_damage_dealt: 0,
+		})
+		.collect::<Vec<_>>();
+
+	// swap sides every round so neither entrant keeps the side that wins speed ties
+	let sides = |round: usize, (first, second): (usize, usize)| {
+		if round % 2 == 0
+		{
+			[first, second]
+		}
+		else
+		{
+			[second, first]
+		}
+	};
+	let game_count = options.games as usize * pairs.len();
+	let results = battle::parallel_map(game_count, options.threads, |game| {
+		let sides = sides(game / pairs.len(), pairs[game % pairs.len()]);
+		battle::play_game(
+			sides.map(|it| entrants[it].team),
+			sides.map(|it| &entrants[it].agent),
+			[options.participants; 2],
+			status_map,
+			move_map,
+			rules,
+			battle::game_seed(options.seed, game),
+			options.max_turns,
+		)
+	});
+
+	// ratings depend on the order games are counted in, so that's done here in order rather than
+	// as the games finish
+	let mut results = results.into_iter();
+	for round in 0..options.games as usize
+	{
+		let mut round_games = vec![Vec::new(); entrants.len()];
+		for (matchup, (first, second)) in matchups.iter_mut().zip(&pairs)
+		{
+			let result = results.next().expect("every game has a result")?;
+			let first_side = usize::from(sides(round, (*first, *second))[0] != *first);
+			matchup.games += 1;
+			matchup.total_turns += u64::from(result.turns);
+			matchup.first_damage_dealt += result.damage_dealt[first_side];
+			matchup.second_damage_dealt += result.damage_dealt[1 - first_side];
+
+			let winner = result.winner;
+			let first_score: Score = match winner
+			{
+				Some(side) if side == first_side => 1.0,
+				Some(_) => 0.0,
+				None => 0.5,
+			};
+			match winner
+			{
+				Some(side) if side == first_side => matchup.first_wins += 1,
+				Some(_) => matchup.second_wins += 1,
+				None => matchup.draws += 1,
+			}
+			record(&mut standings[*first], first_score);
+			record(&mut standings[*second], 1.0 - first_score);
+
+			let mut second_elo = standings[*second].elo;
+			standings[*first].elo.update(&mut second_elo, first_score);
+			standings[*second].elo = second_elo;
+
+			round_games[*first].push((standings[*second].glicko, first_score));
+			round_games[*second].push((standings[*first].glicko, 1.0 - first_score));
+		}
+
+		for (standing, games) in standings.iter_mut().zip(&round_games)
+		{
+			standing.glicko = standing.glicko.updated(games);
+		}
+	}
+
+	standings.sort_by(|a, b| b.elo.rating.total_cmp(&a.elo.rating));
+	Ok(TournamentResult { standings, matchups })
+}
+
+fn record(standing: &mut Standing, score: Score)
+{
+	standing.games += 1;
+	if score > 0.5
+	{
+		standing.wins += 1;
+	}
+	else if score < 0.5
+	{
+		standing.losses += 1;
+	}
+	else
+	{
+		standing.draws += 1;
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{
+		battle::{GreedyAgent, RandomAgent},
+		data::Pokemon,
+		MOVE_MAP, RULESET, SPECIES_MAP, STATUS_MAP,
+	};
+
+	fn team(species: &str, moves: [&str; 2]) -> Team<'static>
+	{
+		Team::new(String::from(species)).add_member(
+			Pokemon::new(&SPECIES_MAP[species])
+				.set_level(50)
+				.add_moves(moves.map(|it| &MOVE_MAP[it])),
+		)
+	}
+
+	#[test]
+	fn plays_out_the_same_for_the_same_seed()
+	{
+		let teams = [
+			team("garchomp", ["dragon_claw", "stone_edge"]),
+			team("snorlax", ["giga_impact", "rest"]),
+			team("gengar", ["shadow_ball", "sludge_bomb"]),
+		];
+		let entrants = || {
+			teams
+				.iter()
+				.enumerate()
+				.map(|(index, team)| Entrant {
+					name: team.name.clone(),
+					team,
+					agent: if index == 0
+					{
+						Box::new(|_| Box::new(GreedyAgent) as Box<dyn Agent>)
+					}
+					else
+					{
+						Box::new(|seed| Box::new(RandomAgent::new(seed)) as Box<dyn Agent>)
+					},
+				})
+				.collect::<Vec<_>>()
+		};
+		let play = |seed, threads| {
+			let options = TournamentOptions {
+				games: 6,
+				max_turns: 200,
+				participants: 1,
+				seed: Some(seed),
+				threads,
+			};
+			let result =
+				run_tournament(&entrants(), &STATUS_MAP, &MOVE_MAP, &RULESET, options).unwrap();
+			serde_json::to_string(&result).unwrap()
+		};
+
+		let result = play(3, 1);
+		assert_eq!(result, play(3, 1));
+		assert_eq!(result, play(3, 4));
+		assert_ne!(result, play(4, 1));
+	}
+}
@@ -0,0 +1,172 @@
+mod battle;
+mod build;
+mod coverage;
+mod grit;
+mod hint;
+mod import_pa8;
+mod infer;
+mod ko;
+mod library;
+mod matchup;
+mod random;
+mod simulate;
+mod speed;
+mod style;
+mod tournament;
+mod validate;
+
+use crate::{
+	data::{Pokemon, Ruleset, StatBlock, Team},
+	parsing, MOVE_MAP, NATURE_MAP, RULESET, SPECIES_MAP,
+};
+
+pub use battle::*;
+pub use build::*;
+pub use coverage::*;
+pub use grit::*;
+pub use hint::*;
+pub use import_pa8::*;
+pub use infer::*;
+pub use ko::*;
+pub use library::*;
+pub use matchup::*;
+pub use random::*;
+pub use simulate::*;
+pub use speed::*;
+pub use style::*;
+pub use tournament::*;
+pub use validate::*;
+
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str>
+{
+	args.iter()
+		.position(|it| it == flag)
+		.and_then(|index| args.get(index + 1))
+		.map(String::as_str)
+}
+
+fn parsed_flag<T>(args: &[String], flag: &str) -> Result<Option<T>, String>
+where
+	T: std::str::FromStr,
+{
+	flag_value(args, flag)
+		.map(|it| {
+			it.parse::<T>()
+				.map_err(|_| format!("could not understand '{it}' for {flag}"))
+		})
+		.transpose()
+}
+
+/// Reads a paste file. Mainline EVs in it are converted to effort levels if `--convert-evs` was
+/// given, which every command that reads pastes takes
+fn read_team(file: &str, args: &[String]) -> Result<Team<'static>, String>
+{
+	let data = std::fs::read_to_string(file).map_err(|err| format!("{file}: {err}"))?;
+	parse_team(file, &data, has_flag(args, "--convert-evs"))
+}
+
+/// Parses the contents of a paste file, printing anything in it that had to be dropped to stderr
+fn parse_team(file: &str, data: &str, convert_evs: bool) -> Result<Team<'static>, String>
+{
+	let (team, warnings) = parsing::deserialize_team_with(
+		data,
+		&SPECIES_MAP,
+		&MOVE_MAP,
+		&NATURE_MAP,
+		parsing::ParseOptions { convert_evs },
+	)
+	.map_err(|err| format!("{file}: {err}"))?;
+	for warning in warnings
+	{
+		eprintln!("{file}: warning: {warning}");
+	}
+
+	Ok(team)
+}
+
+/// The ruleset file given with `--rules`, or the standard one
+fn ruleset(args: &[String]) -> Result<Ruleset, String>
+{
+	flag_value(args, "--rules").map_or_else(
+		|| Ok(RULESET.clone()),
+		|file| {
+			let data = std::fs::read_to_string(file).map_err(|err| format!("{file}: {err}"))?;
+			let rules = toml::from_str::<Ruleset>(&data).map_err(|err| format!("{file}: {err}"))?;
+			rules.validate().map_err(|err| format!("{file}: {err}"))?;
+			Ok(rules)
+		},
+	)
+}
+
+/// Member `number` of the team counting from 1, or the first member if no number is given
+fn team_member<'t, 'a>(team: &'t Team<'a>, number: Option<usize>) -> Result<&'t Pokemon<'a>, String>
+{
+	let number = number.unwrap_or(1);
+	number
+		.checked_sub(1)
+		.and_then(|it| team.members.get(it))
+		.ok_or_else(|| format!("{} has no member number {number}", team.name))
+}
+
+/// Reads six slash separated numbers, in hp/atk/def/spa/spd/spe order
+fn parse_stat_block(string: &str) -> Option<StatBlock>
+{
+	let values = string
+		.split('/')
+		.map(|it| it.trim().parse::<i32>())
+		.collect::<Result<Vec<_>, _>>()
+		.ok()?;
+	let [hp, atk, def, spatk, spdef, spe] = values[..]
+	else
+	{
+		return None;
+	};
+
+	Some(StatBlock {
+		hp,
+		atk,
+		def,
+		spatk,
+		spdef,
+		spe,
+	})
+}
+
+/// `field` as a CSV field, quoted as RFC 4180 asks if it has anything in it that needs quoting
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str>
+{
+	if field.contains([',', '"', '\n', '\r'])
+	{
+		format!("\"{}\"", field.replace('"', "\"\"")).into()
+	}
+	else
+	{
+		field.into()
+	}
+}
+
+fn has_flag(args: &[String], flag: &str) -> bool
+{
+	args.iter().any(|it| it == flag)
+}
+
+/// The arguments that are left once every `--flag value` pair in `value_flags` and every bare
+/// `--flag` has been taken out
+fn positional<'a>(args: &'a [String], value_flags: &[&str]) -> Vec<&'a str>
+{
+	let mut positional = Vec::new();
+	let mut iter = args.iter();
+	while let Some(arg) = iter.next()
+	{
+		if value_flags.contains(&arg.as_str())
+		{
+			iter.next();
+		}
+		else if !arg.starts_with("--")
+		{
+			positional.push(arg.as_str());
+		}
+	}
+
+	positional
+}
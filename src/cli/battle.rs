@@ -0,0 +1,190 @@
+use crate::{
+	battle::{
+		Agent, Battle, Budget, ExpectiminimaxAgent, GreedyAgent, HeuristicAgent, MctsAgent, RandomAgent,
+		SerBattle,
+	},
+	data::BossRules,
+	MOVE_MAP, SPECIES_MAP, STATUS_MAP,
+};
+
+const USAGE: &str = "usage: battle <paste file> <paste file> [options]
+       battle --resume <save file> [options]
+	--agents <a,b>        the agent for each side: random, greedy, heuristic, expectiminimax[:depth]
+	                      or mcts[:iterations] (default greedy,greedy)
+	--participants <a,b>  how many pokemon each side has out at once (default 1,1)
+	--doubles             the same as --participants 2,2
+	--seed <n>            seed for the battle and any random agents
+	--max-turns <n>       give up after this many actions (default 500)
+	--rules <file>        use a different ruleset
+	--boss <file>         a noble battle against the first pokemon of the second team, by the
+	                      rules in the file (see assets/nobles)
+	--save <file>         save the battle where it stopped, to carry on with later
+	--resume <file>       carry on with a saved battle, playing by the ruleset it was saved with
+	--convert-evs         read mainline EVs in pastes as effort levels";
+
+pub fn battle_command(args: &[String]) -> Result<(), String>
+{
+	let value_flags = [
+		"--agents",
+		"--participants",
+		"--seed",
+		"--max-turns",
+		"--rules",
+		"--boss",
+		"--save",
+		"--resume",
+	];
+	let saved = super::flag_value(args, "--resume")
+		.map(read_save)
+		.transpose()?;
+	let teams = match (&saved, &super::positional(args, &value_flags)[..])
+	{
+		(None, [first, second]) => [
+			super::read_team(first, args)?,
+			super::read_team(second, args)?,
+		],
+		(Some(saved), []) => saved
+			.teams(&SPECIES_MAP, &MOVE_MAP)
+			.map_err(|err| err.to_string())?,
+		_ => return Err(String::from(USAGE)),
+	};
+	let rules = match &saved
+	{
+		Some(_) if super::has_flag(args, "--rules") =>
+		{
+			return Err(String::from(
+				"a resumed battle plays by the ruleset it was saved with, leave out --rules",
+			));
+		}
+		Some(saved) => saved.rules.clone(),
+		None => super::ruleset(args)?,
+	};
+	let seed = super::parsed_flag::<u64>(args, "--seed")?;
+	let max_turns = super::parsed_flag(args, "--max-turns")?.unwrap_or(500);
+
+	let names = super::flag_value(args, "--agents").unwrap_or("greedy,greedy");
+	let [first_agent, second_agent] = names.split(',').collect::<Vec<_>>()[..]
+	else
+	{
+		return Err(format!("expected two agents separated by a comma, got '{names}'"));
+	};
+	let mut first_agent = agent_from_name(first_agent, seed)?;
+	let mut second_agent = agent_from_name(second_agent, seed.map(|it| it.wrapping_add(1)))?;
+
+	let teams = [&teams[0], &teams[1]];
+	let resumed = saved.is_some();
+	let boss = super::flag_value(args, "--boss").map(read_boss).transpose()?;
+	let mut battle = match (saved, boss)
+	{
+		(Some(saved), _) => saved.into_battle(teams, &STATUS_MAP, &MOVE_MAP, &rules),
+		(None, boss) => Battle::with_participants(
+			teams,
+			participants(args)?,
+			&STATUS_MAP,
+			&MOVE_MAP,
+			&rules,
+			seed,
+		)
+		.and_then(|battle| match boss
+		{
+			Some(boss) => battle.with_boss(boss, &MOVE_MAP),
+			None => Ok(battle),
+		}),
+	}
+	.map_err(|err| err.to_string())?;
+	// a resumed battle has already shown what happened before it was saved
+	let shown = if resumed { battle.events().len() } else { 0 };
+	if resumed
+	{
+		println!("carrying on from turn {}", battle.turn());
+	}
+
+	let result = battle.run(&mut [&mut *first_agent, &mut *second_agent], max_turns);
+	for event in &battle.events()[shown..]
+	{
+		println!("{event}");
+	}
+	if result.map_err(|err| err.to_string())?.is_none() && battle.turn() >= max_turns
+	{
+		println!("stopped after {max_turns} turns");
+	}
+
+	if let Some(file) = super::flag_value(args, "--save")
+	{
+		let json = serde_json::to_string_pretty(&SerBattle::new(&battle, teams))
+			.map_err(|err| err.to_string())?;
+		std::fs::write(file, json).map_err(|err| format!("{file}: {err}"))?;
+	}
+
+	Ok(())
+}
+
+fn read_boss(file: &str) -> Result<BossRules, String>
+{
+	let data = std::fs::read_to_string(file).map_err(|err| format!("{file}: {err}"))?;
+	let rules = toml::from_str::<BossRules>(&data).map_err(|err| format!("{file}: {err}"))?;
+	rules.validate().map_err(|err| format!("{file}: {err}"))?;
+	Ok(rules)
+}
+
+fn read_save(file: &str) -> Result<SerBattle, String>
+{
+	let data = std::fs::read_to_string(file).map_err(|err| format!("{file}: {err}"))?;
+	let saved = serde_json::from_str::<SerBattle>(&data).map_err(|err| format!("{file}: {err}"))?;
+	saved.rules.validate().map_err(|err| format!("{file}: {err}"))?;
+	if let Some(boss) = &saved.boss
+	{
+		boss.rules.validate().map_err(|err| format!("{file}: {err}"))?;
+	}
+	Ok(saved)
+}
+
+/// How many pokemon each side has out at once, from `--participants a,b` or `--doubles`
+pub(super) fn participants(args: &[String]) -> Result<[usize; 2], String>
+{
+	let Some(value) = super::flag_value(args, "--participants")
+	else
+	{
+		return Ok(if super::has_flag(args, "--doubles") { [2, 2] } else { [1, 1] });
+	};
+	let counts = value
+		.split(',')
+		.map(|it| it.trim().parse::<usize>())
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|_| format!("could not understand '{value}' for --participants"))?;
+	match counts[..]
+	{
+		[first, second] if first > 0 && second > 0 => Ok([first, second]),
+		_ => Err(format!("expected two counts above 0 separated by a comma, got '{value}'")),
+	}
+}
+
+pub(super) fn agent_from_name(name: &str, seed: Option<u64>) -> Result<Box<dyn Agent>, String>
+{
+	let (kind, amount) = match name.trim().split_once(':')
+	{
+		Some((kind, amount)) =>
+		{
+			let amount = amount
+				.parse::<u32>()
+				.map_err(|_| format!("'{amount}' isn't a search depth or iteration count"))?;
+			(kind, Some(amount))
+		}
+		None => (name.trim(), None),
+	};
+
+	match (kind, amount)
+	{
+		("random", None) => Ok(Box::new(RandomAgent::new(seed))),
+		("greedy", None) => Ok(Box::new(GreedyAgent)),
+		("heuristic", None) => Ok(Box::new(HeuristicAgent)),
+		("expectiminimax", depth) => Ok(Box::new(
+			depth.map_or_else(ExpectiminimaxAgent::default, ExpectiminimaxAgent::new),
+		)),
+		("mcts", iterations) => Ok(Box::new(MctsAgent::new(
+			Budget::Iterations(iterations.unwrap_or(MctsAgent::DEFAULT_ITERATIONS)),
+			seed,
+		))),
+		_ => Err(format!("no agent called '{name}'")),
+	}
+}
@@ -0,0 +1,27 @@
+use crate::{parsing, teambuilder::TeamBuilder, MOVE_MAP, NATURE_MAP, SPECIES_MAP};
+
+pub fn build_command(args: &[String]) -> Result<(), String>
+{
+	let rules = super::ruleset(args)?;
+	let mut builder = TeamBuilder::new(
+		std::io::BufReader::new(std::io::stdin()),
+		std::io::stdout(),
+		&SPECIES_MAP,
+		&MOVE_MAP,
+		&NATURE_MAP,
+		&rules,
+	);
+	if let Some(file) = super::flag_value(args, "--load")
+	{
+		builder = builder.with_team(super::read_team(file, args)?);
+	}
+
+	let team = builder.run().map_err(|err| err.to_string())?;
+	if let Some(file) = super::flag_value(args, "--out")
+	{
+		std::fs::write(file, parsing::serialize_team(&team, &NATURE_MAP))
+			.map_err(|err| format!("{file}: {err}"))?;
+	}
+
+	Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::{analysis, TYPE_CHART, TYPE_MAP};
+
+const USAGE: &str = "usage: coverage <paste file> [--shared <n>] [--convert-evs]
+--shared sets how many members must share a weakness for it to be flagged (default 2)";
+
+pub fn coverage_command(args: &[String]) -> Result<(), String>
+{
+	let [file] = super::positional(args, &["--shared"])[..]
+	else
+	{
+		return Err(String::from(USAGE));
+	};
+	let team = super::read_team(file, args)?;
+	let shared = super::parsed_flag(args, "--shared")?.unwrap_or(2);
+	let report = analysis::analyze_coverage(&team, &TYPE_MAP, &TYPE_CHART);
+
+	println!("defensive profile");
+	print!("{:<10}", "");
+	for member in &team.members
+	{
+		print!(" {:>10.10}", member.name());
+	}
+	println!();
+	for row in &report.defensive
+	{
+		print!("{:<10}", row.attacking_type.id);
+		for multiplier in &row.multipliers
+		{
+			print!(" {:>10}", format!("x{multiplier}"));
+		}
+		println!(
+			"{}",
+			if row.weak_count() >= shared { "  !" } else { "" }
+		);
+	}
+
+	println!("\noffensive coverage");
+	for row in &report.offensive
+	{
+		let moves = row
+			.super_effective_moves
+			.iter()
+			.map(|(i, mv)| format!("{mv} ({})", team.members[*i].name()))
+			.collect::<Vec<_>>()
+			.join(", ");
+		println!(
+			"{:<10} {:>5}  {moves}",
+			row.defending_type.id,
+			format!("x{}", row.best_multiplier)
+		);
+	}
+
+	let join = |types: Vec<&crate::data::Type>| {
+		types
+			.iter()
+			.map(|it| &*it.id)
+			.collect::<Vec<_>>()
+			.join(", ")
+	};
+	println!(
+		"\nshared weaknesses: {}",
+		join(report.shared_weaknesses(shared))
+	);
+	println!("not covered: {}", join(report.uncovered()));
+
+	Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::{
+	analysis::{self, GritCounts, GritItem},
+	GRIT_TABLE,
+};
+
+const USAGE: &str = "usage: grit <current els> <target els> [--dust <n>] [--gravel <n>] [--pebble <n>] \
+                     [--rock <n>]
+effort levels are written hp/atk/def/spa/spd/spe, and items left out are assumed to be unlimited";
+
+const ITEM_NAMES: [&str; 4] = ["dust", "gravel", "pebble", "rock"];
+
+pub fn grit_command(args: &[String]) -> Result<(), String>
+{
+	let [current, target] = super::positional(args, &["--dust", "--gravel", "--pebble", "--rock"])[..]
+	else
+	{
+		return Err(String::from(USAGE));
+	};
+	let current = super::parse_stat_block(current)
+		.ok_or_else(|| format!("could not understand effort levels '{current}'\n{USAGE}"))?;
+	let target = super::parse_stat_block(target)
+		.ok_or_else(|| format!("could not understand effort levels '{target}'\n{USAGE}"))?;
+
+	let mut inventory = GritCounts::default();
+	for (item, name) in GritItem::ALL.into_iter().zip(ITEM_NAMES)
+	{
+		inventory[item] = super::parsed_flag(args, &format!("--{name}"))?.unwrap_or(u32::MAX);
+	}
+
+	let plan = analysis::plan_grit(&current, &target, &inventory, &GRIT_TABLE)
+		.map_err(|err| err.to_string())?;
+	for stat in &plan.stats
+	{
+		println!(
+			"{:<6} {:>2} -> {:<2}  {}  (value {})",
+			format!("{:?}", stat.stat),
+			stat.from,
+			stat.to,
+			format_counts(&stat.items),
+			stat.value
+		);
+	}
+	println!(
+		"total            {}  (value {})",
+		format_counts(&plan.total),
+		plan.total_value
+	);
+
+	Ok(())
+}
+
+fn format_counts(counts: &GritCounts) -> String
+{
+	GritItem::ALL
+		.into_iter()
+		.zip(ITEM_NAMES)
+		.map(|(item, name)| format!("{} {name}", counts[item]))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
@@ -0,0 +1,107 @@
+use crate::{
+	battle::{Action, Battle, BattleHistory, Budget, Decision, MctsAgent},
+	MOVE_MAP, STATUS_MAP,
+};
+
+const USAGE: &str = "usage: hint <paste file> <paste file> [options]
+	--agents <a,b>        agents that play out the battle first (default greedy,greedy)
+	--turns <n>           how many actions to play before asking for a hint (default 0)
+	--undo <n>            take back this many of those actions afterwards (default 0)
+	--participants <a,b>  how many pokemon each side has out at once (default 1,1)
+	--doubles             the same as --participants 2,2
+	--iterations <n>      how many searches to run (default 1000)
+	--time-ms <n>         search for this long instead of a set number of iterations
+	--seed <n>            seed for the battle, the agents and the search
+	--rules <file>        use a different ruleset
+	--convert-evs         read mainline EVs in pastes as effort levels";
+
+pub fn hint_command(args: &[String]) -> Result<(), String>
+{
+	let value_flags = [
+		"--agents",
+		"--turns",
+		"--undo",
+		"--participants",
+		"--iterations",
+		"--time-ms",
+		"--seed",
+		"--rules",
+	];
+	let [first, second] = super::positional(args, &value_flags)[..]
+	else
+	{
+		return Err(String::from(USAGE));
+	};
+	let teams = [super::read_team(first, args)?, super::read_team(second, args)?];
+	let rules = super::ruleset(args)?;
+	let seed = super::parsed_flag::<u64>(args, "--seed")?;
+	let turns = super::parsed_flag(args, "--turns")?.unwrap_or(0);
+	let undo = super::parsed_flag(args, "--undo")?.unwrap_or(0);
+	let budget = match super::parsed_flag(args, "--time-ms")?
+	{
+		Some(ms) => Budget::Time(std::time::Duration::from_millis(ms)),
+		None => Budget::Iterations(
+			super::parsed_flag(args, "--iterations")?.unwrap_or(MctsAgent::DEFAULT_ITERATIONS),
+		),
+	};
+
+	let names = super::flag_value(args, "--agents").unwrap_or("greedy,greedy");
+	let [first_agent, second_agent] = names.split(',').collect::<Vec<_>>()[..]
+	else
+	{
+		return Err(format!("expected two agents separated by a comma, got '{names}'"));
+	};
+	let mut first_agent = super::agent_from_name(first_agent, seed)?;
+	let mut second_agent = super::agent_from_name(second_agent, seed.map(|it| it.wrapping_add(1)))?;
+
+	let participants = super::participants(args)?;
+	let battle = Battle::with_participants(
+		[&teams[0], &teams[1]],
+		participants,
+		&STATUS_MAP,
+		&MOVE_MAP,
+		&rules,
+		seed,
+	)
+	.map_err(|err| err.to_string())?;
+	let mut history = BattleHistory::new(battle);
+	history
+		.run(&mut [&mut *first_agent, &mut *second_agent], turns)
+		.map_err(|err| err.to_string())?;
+	for _ in 0..undo
+	{
+		history.undo();
+	}
+	for event in history.events()
+	{
+		println!("{event}");
+	}
+	let battle = history.battle();
+
+	let (Decision::Action(position) | Decision::Replacement(position)) = battle.decision()
+	else
+	{
+		println!("the battle is over");
+		return Ok(());
+	};
+	let stats = MctsAgent::new(budget, seed).analyze(battle);
+	let describe = |action: &Action| match action
+	{
+		Action::Move { mv, style, target } => format!("{} ({style:?}) on {target}", mv.id),
+		Action::Switch(member) =>
+		{
+			let species = &battle.side(position.side).party[*member].pokemon.species.id;
+			format!("switch to {species}")
+		}
+	};
+
+	println!();
+	println!("best move here for {position} is {}", describe(&stats[0].action));
+	println!("{:<32}{:>8}{:>8}", "action", "visits", "value");
+	for it in &stats
+	{
+		println!("{:<32}{:>8}{:>8.2}", describe(&it.action), it.visits, it.value);
+	}
+
+	Ok(())
+}
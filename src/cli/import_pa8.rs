@@ -0,0 +1,50 @@
+use crate::{
+	data::Team,
+	library::{TeamFormat, TeamLibrary},
+	pa8::Pa8,
+	parsing, GAME_IDS, MOVE_MAP, NATURE_MAP, SPECIES_MAP,
+};
+
+const USAGE: &str = "usage: import-pa8 <file>... [--save <team name>] [--dir <path>] [--json]";
+
+pub fn import_pa8_command(args: &[String]) -> Result<(), String>
+{
+	let files = super::positional(args, &["--save", "--dir"]);
+	if files.is_empty()
+	{
+		return Err(String::from(USAGE));
+	}
+
+	let mut team = Team::new(String::from(
+		super::flag_value(args, "--save").unwrap_or("Imported"),
+	));
+	for file in files
+	{
+		let data = std::fs::read(file).map_err(|err| format!("{file}: {err}"))?;
+		let pokemon = Pa8::decode(&data)
+			.and_then(|it| it.to_pokemon(&GAME_IDS, &SPECIES_MAP, &MOVE_MAP, &NATURE_MAP))
+			.map_err(|err| format!("{file}: {err}"))?;
+		team = team.add_member(pokemon);
+	}
+
+	if super::has_flag(args, "--save")
+	{
+		let format = if super::has_flag(args, "--json")
+		{
+			TeamFormat::Json
+		}
+		else
+		{
+			TeamFormat::Paste
+		};
+		TeamLibrary::open(super::flag_value(args, "--dir").unwrap_or("teams"))
+			.and_then(|library| library.create(&team, format, &NATURE_MAP))
+			.map_err(|err| err.to_string())?;
+	}
+	else
+	{
+		println!("{}", parsing::serialize_team(&team, &NATURE_MAP));
+	}
+
+	Ok(())
+}
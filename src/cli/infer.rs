@@ -0,0 +1,57 @@
+use crate::{
+	analysis,
+	data::Stat,
+	parsing, NATURE_MAP, SPECIES_MAP,
+};
+
+const STAT_LABELS: [&str; 6] = ["HP", "Atk", "Def", "SpA", "SpD", "Spe"];
+const USAGE: &str = "usage: infer <species> <level> <hp>/<atk>/<def>/<spa>/<spd>/<spe> [--alpha]";
+
+pub fn infer_command(args: &[String]) -> Result<(), String>
+{
+	let [species, level, stats] = super::positional(args, &[])[..]
+	else
+	{
+		return Err(String::from(USAGE));
+	};
+
+	let species = SPECIES_MAP
+		.get(&*parsing::pokemon_id_from(species))
+		.ok_or_else(|| format!("could not find species '{species}'"))?;
+	let level = level
+		.parse::<u8>()
+		.map_err(|_| format!("could not understand level '{level}'"))?;
+	let observed = super::parse_stat_block(stats)
+		.ok_or_else(|| format!("could not understand stats '{stats}'\n{USAGE}"))?;
+
+	let is_alpha = super::has_flag(args, "--alpha");
+	let spreads = analysis::infer_spreads(species, level, is_alpha, &observed, &NATURE_MAP);
+	if spreads.is_empty()
+	{
+		println!("no nature and effort levels can produce those stats");
+	}
+	for spread in spreads
+	{
+		let options = Stat::ALL
+			.iter()
+			.zip(STAT_LABELS)
+			.map(|(stat, label)| {
+				let levels = spread
+					.options_for(*stat)
+					.iter()
+					.map(i32::to_string)
+					.collect::<Vec<_>>()
+					.join(",");
+				format!("{levels} {label}")
+			})
+			.collect::<Vec<_>>()
+			.join(" / ");
+		println!(
+			"{:<8} {options} ({} combinations)",
+			parsing::display_name_from(&spread.nature_name),
+			spread.combination_count()
+		);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::{
+	analysis,
+	data::{BattlePokemon, Style},
+	parsing, MOVE_MAP, STATUS_MAP,
+};
+
+const USAGE: &str = "usage: ko <attacking paste file> <defending paste file> <move> [options]
+	--attacker <n>        use member n of the attacking team (default 1)
+	--target <n>          use member n of the defending team (default 1)
+	--style <style>       only show regular, agile or strong
+	--hits <n>            how many attacks to show chances for (default 4)
+	--status <id>         give the target a status first, ie. burn or poison
+	--status-turns <n>    how many of the target's turns the status lasts (default 3)
+	--rules <file>        use a different ruleset
+	--convert-evs         read mainline EVs in pastes as effort levels";
+
+const VALUE_FLAGS: [&str; 7] = [
+	"--attacker",
+	"--target",
+	"--style",
+	"--hits",
+	"--status",
+	"--status-turns",
+	"--rules",
+];
+
+pub fn ko_command(args: &[String]) -> Result<(), String>
+{
+	let [attacking, defending, move_name] = super::positional(args, &VALUE_FLAGS)[..]
+	else
+	{
+		return Err(String::from(USAGE));
+	};
+	let attacking = super::read_team(attacking, args)?;
+	let defending = super::read_team(defending, args)?;
+	let attacker = super::team_member(&attacking, super::parsed_flag(args, "--attacker")?)?;
+	let target = super::team_member(&defending, super::parsed_flag(args, "--target")?)?;
+
+	let mv = MOVE_MAP
+		.get(&parsing::move_id_from(move_name))
+		.ok_or_else(|| format!("no move called '{move_name}'"))?;
+	let hits = super::parsed_flag(args, "--hits")?.unwrap_or(4);
+
+	let rules = super::ruleset(args)?;
+	let attacker = BattlePokemon::new(attacker, &rules);
+	let mut target = BattlePokemon::new(target, &rules);
+	if let Some(status) = super::flag_value(args, "--status")
+	{
+		let condition = STATUS_MAP
+			.get(status)
+			.ok_or_else(|| format!("no status called '{status}'"))?;
+		let turns = super::parsed_flag(args, "--status-turns")?.unwrap_or(3);
+		target.apply_status(condition, turns, mv);
+	}
+
+	let styles = super::parsed_flag::<Style>(args, "--style")?
+		.map_or_else(|| Style::ALL.to_vec(), |style| vec![style]);
+
+	println!(
+		"{} using {} on {} ({} hp)",
+		attacker.pokemon.name(),
+		parsing::display_name_from(&mv.id),
+		target.pokemon.name(),
+		target.current_hp()
+	);
+	for style in styles
+	{
+		let chances = analysis::ko_chances(&attacker, &target, mv, style, hits);
+		let within = chances
+			.within
+			.iter()
+			.enumerate()
+			.map(|(i, chance)| format!("{}HKO {:.2}%", i + 1, chance * 100.0))
+			.collect::<Vec<_>>()
+			.join("  ");
+		println!(
+			"  {:<8} hit {:>5.1}%  crit {:>5.1}%  {within}",
+			format!("{style:?}"),
+			chances.hit_chance * 100.0,
+			chances.crit_chance * 100.0
+		);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,135 @@
+use crate::{
+	data::{serialization::SerTeam, Team},
+	library::{SearchQuery, TeamEntry, TeamFormat, TeamLibrary},
+	parsing, MOVE_MAP, NATURE_MAP, SPECIES_MAP, TYPE_MAP,
+};
+
+const USAGE: &str = "usage: library [--dir <path>] <command>
+commands:
+	list [--folder <folder>]
+	show <name> [--json]
+	create <name> <file> [--json] [--convert-evs]
+	rename <name> <new name>
+	duplicate <name> <new name>
+	delete <name>
+	tag <name> <folder>
+	untag <name> <folder>
+	search <species|move|type> <id>";
+
+pub fn library_command(args: &[String]) -> Result<(), String>
+{
+	let library = TeamLibrary::open(super::flag_value(args, "--dir").unwrap_or("teams"))
+		.map_err(|err| err.to_string())?;
+	let positional = super::positional(args, &["--dir", "--folder"]);
+
+	match positional.as_slice()
+	{
+		["list"] =>
+		{
+			let entries = super::flag_value(args, "--folder")
+				.map_or_else(|| library.list(), |folder| library.in_folder(folder))
+				.map_err(|err| err.to_string())?;
+			print_entries(&entries);
+		}
+		["show", name] =>
+		{
+			let team = library
+				.load(name, &SPECIES_MAP, &MOVE_MAP, &NATURE_MAP)
+				.map_err(|err| err.to_string())?;
+			if super::has_flag(args, "--json")
+			{
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&SerTeam::from(&team))
+						.map_err(|err| err.to_string())?
+				);
+			}
+			else
+			{
+				println!("{}", parsing::serialize_team(&team, &NATURE_MAP));
+			}
+		}
+		["create", name, file] =>
+		{
+			let mut team = read_team(file, super::has_flag(args, "--convert-evs"))?;
+			team.name = String::from(*name);
+			let format = if super::has_flag(args, "--json")
+			{
+				TeamFormat::Json
+			}
+			else
+			{
+				TeamFormat::Paste
+			};
+			library
+				.create(&team, format, &NATURE_MAP)
+				.map_err(|err| err.to_string())?;
+		}
+		["rename", name, new_name] => library
+			.rename(name, new_name)
+			.map_err(|err| err.to_string())?,
+		["duplicate", name, new_name] => library
+			.duplicate(name, new_name)
+			.map_err(|err| err.to_string())?,
+		["delete", name] => library.delete(name).map_err(|err| err.to_string())?,
+		["tag", name, folder] => library.tag(name, folder).map_err(|err| err.to_string())?,
+		["untag", name, folder] => library
+			.untag(name, folder)
+			.map_err(|err| err.to_string())?,
+		["search", kind, id] =>
+		{
+			let id = Box::from(*id);
+			let query = match *kind
+			{
+				"species" => SearchQuery::Species(id),
+				"move" => SearchQuery::Move(id),
+				"type" => SearchQuery::Type(id),
+				_ => return Err(format!("cannot search by '{kind}'\n{USAGE}")),
+			};
+			let results = library
+				.search(&query, &SPECIES_MAP, &MOVE_MAP, &NATURE_MAP, &TYPE_MAP)
+				.map_err(|err| err.to_string())?;
+			for (entry, err) in &results.skipped
+			{
+				eprintln!("warning: skipping '{}': {err}", entry.name);
+			}
+			print_entries(&results.found);
+		}
+		_ => return Err(String::from(USAGE)),
+	}
+
+	Ok(())
+}
+
+fn read_team(file: &str, convert_evs: bool) -> Result<Team<'static>, String>
+{
+	let data = std::fs::read_to_string(file).map_err(|err| format!("{file}: {err}"))?;
+	if std::path::Path::new(file)
+		.extension()
+		.is_some_and(|it| it.eq_ignore_ascii_case("json"))
+	{
+		serde_json::from_str::<SerTeam>(&data)
+			.map_err(|err| err.to_string())?
+			.into_team(&SPECIES_MAP, &MOVE_MAP)
+			.map_err(|err| format!("could not find '{}'", err.0))
+	}
+	else
+	{
+		super::parse_team(file, &data, convert_evs)
+	}
+}
+
+fn print_entries(entries: &[TeamEntry])
+{
+	for entry in entries
+	{
+		if entry.folders.is_empty()
+		{
+			println!("{}", entry.name);
+		}
+		else
+		{
+			println!("{} [{}]", entry.name, entry.folders.join(", "));
+		}
+	}
+}
@@ -0,0 +1,74 @@
+use crate::{analysis, parsing};
+
+const USAGE: &str = "usage: matchup <attacking paste file> <defending paste file> [--csv] [--rules <file>] [--convert-evs]
+shows the damage range and min-max percent of hp every attacking move deals to every defender, in each style";
+
+pub fn matchup_command(args: &[String]) -> Result<(), String>
+{
+	let [attacking, defending] = super::positional(args, &["--rules"])[..]
+	else
+	{
+		return Err(String::from(USAGE));
+	};
+	let attackers = super::read_team(attacking, args)?;
+	let defenders = super::read_team(defending, args)?;
+	let rules = super::ruleset(args)?;
+	let rows = analysis::damage_matrix(&attackers, &defenders, &rules);
+
+	if super::has_flag(args, "--csv")
+	{
+		println!("attacker,move,style,defender,defender_hp,min_damage,max_damage,min_percent,max_percent");
+		for row in &rows
+		{
+			for cell in &row.cells
+			{
+				println!(
+					"{},{},{},{},{},{},{},{:.1},{:.1}",
+					super::csv_field(attackers.members[row.attacker].name()),
+					super::csv_field(&row.mv.id),
+					format!("{:?}", row.style).to_lowercase(),
+					super::csv_field(defenders.members[cell.defender].name()),
+					cell.defender_hp,
+					cell.min_damage,
+					cell.max_damage,
+					cell.min_percent(),
+					cell.max_percent()
+				);
+			}
+		}
+		return Ok(());
+	}
+
+	print!("{:<34}", "");
+	for defender in &defenders.members
+	{
+		print!(" {:>18.18}", defender.name());
+	}
+	println!();
+	for row in &rows
+	{
+		let label = format!(
+			"{} {} ({:?})",
+			attackers.members[row.attacker].name(),
+			parsing::display_name_from(&row.mv.id),
+			row.style
+		);
+		print!("{label:<34.34}");
+		for cell in &row.cells
+		{
+			print!(
+				" {:>18}",
+				format!(
+					"{}-{} {:.0}-{:.0}%",
+					cell.min_damage,
+					cell.max_damage,
+					cell.min_percent(),
+					cell.max_percent()
+				)
+			);
+		}
+		println!();
+	}
+
+	Ok(())
+}
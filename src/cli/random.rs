@@ -0,0 +1,42 @@
+use crate::{
+	generator::{Generator, GeneratorOptions, LevelScaling},
+	parsing, MOVE_MAP, NATURE_MAP, SPECIES_MAP,
+};
+
+const USAGE: &str = "usage: random [--seed <n>] [--count <n>] [--level <n> | --scaled <min>-<max>] \
+                     [--stab <weight>] [--coverage <weight>]";
+
+pub fn random_command(args: &[String]) -> Result<(), String>
+{
+	let defaults = GeneratorOptions::default();
+	let level_scaling = if let Some(range) = super::flag_value(args, "--scaled")
+	{
+		let (min_level, max_level) = range
+			.split_once('-')
+			.and_then(|(min, max)| Some((min.parse().ok()?, max.parse().ok()?)))
+			.ok_or_else(|| format!("could not understand level range '{range}'\n{USAGE}"))?;
+		LevelScaling::BaseStatTotal {
+			min_level,
+			max_level,
+		}
+	}
+	else
+	{
+		super::parsed_flag(args, "--level")?.map_or(defaults.level_scaling, LevelScaling::Fixed)
+	};
+
+	let options = GeneratorOptions {
+		seed: super::parsed_flag(args, "--seed")?,
+		level_scaling,
+		stab_weight: super::parsed_flag(args, "--stab")?.unwrap_or(defaults.stab_weight),
+		coverage_weight: super::parsed_flag(args, "--coverage")?
+			.unwrap_or(defaults.coverage_weight),
+	};
+	options.validate()?;
+
+	let team = Generator::new(&SPECIES_MAP, &MOVE_MAP, &NATURE_MAP, options)
+		.team(super::parsed_flag(args, "--count")?.unwrap_or(6));
+	println!("{}", parsing::serialize_team(&team, &NATURE_MAP));
+
+	Ok(())
+}
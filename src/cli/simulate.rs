@@ -0,0 +1,91 @@
+use crate::{
+	battle::{self, AgentFactory, BatchOptions},
+	MOVE_MAP, STATUS_MAP,
+};
+
+const USAGE: &str = "usage: simulate <paste file> <paste file> [options]
+plays many battles between two teams at once and totals up how they went
+	--agents <a,b>        the agent for each side, see battle (default greedy,greedy)
+	--games <n>           how many battles to play (default 100)
+	--max-turns <n>       a battle is a draw after this many actions (default 500)
+	--participants <a,b>  how many pokemon each side has out at once (default 1,1)
+	--doubles             the same as --participants 2,2
+	--seed <n>            seed for every battle and agent
+	--threads <n>         threads to play battles on (default one per core)
+	--rules <file>        use a different ruleset
+	--json                print every battle's result as json
+	--convert-evs         read mainline EVs in pastes as effort levels";
+
+pub fn simulate_command(args: &[String]) -> Result<(), String>
+{
+	let value_flags =
+		["--agents", "--games", "--max-turns", "--participants", "--seed", "--threads", "--rules"];
+	let [first, second] = super::positional(args, &value_flags)[..]
+	else
+	{
+		return Err(String::from(USAGE));
+	};
+	let teams = [super::read_team(first, args)?, super::read_team(second, args)?];
+	let rules = super::ruleset(args)?;
+	let options = BatchOptions {
+		games: super::parsed_flag(args, "--games")?.unwrap_or(100),
+		max_turns: super::parsed_flag(args, "--max-turns")?.unwrap_or(500),
+		participants: super::participants(args)?,
+		seed: super::parsed_flag(args, "--seed")?,
+		threads: super::parsed_flag(args, "--threads")?.unwrap_or_else(battle::default_threads),
+	};
+
+	let names = super::flag_value(args, "--agents").unwrap_or("greedy,greedy");
+	let [first_agent, second_agent] = names.split(',').collect::<Vec<_>>()[..]
+	else
+	{
+		return Err(format!("expected two agents separated by a comma, got '{names}'"));
+	};
+	let agents = [first_agent, second_agent].map(|name| -> Result<AgentFactory, String> {
+		super::agent_from_name(name, None)?;
+		let name = name.to_owned();
+		Ok(Box::new(move |seed| {
+			super::agent_from_name(&name, seed).expect("the name was checked already")
+		}))
+	});
+	let [Ok(first_agent), Ok(second_agent)] = agents
+	else
+	{
+		return Err(agents.into_iter().find_map(Result::err).unwrap_or_default());
+	};
+
+	let result = battle::simulate_batch(
+		[&teams[0], &teams[1]],
+		[&first_agent, &second_agent],
+		&STATUS_MAP,
+		&MOVE_MAP,
+		&rules,
+		options,
+	)
+	.map_err(|err| err.to_string())?;
+
+	if super::has_flag(args, "--json")
+	{
+		let json = serde_json::to_string_pretty(&result).map_err(|err| err.to_string())?;
+		println!("{json}");
+		return Ok(());
+	}
+
+	let games = result.games.len().max(1) as f64;
+	println!("games: {}", result.games.len());
+	for side in 0..2
+	{
+		println!(
+			"side {} ({}): {} wins ({:.1}%), {:.1} damage dealt per game",
+			side + 1,
+			[first, second][side],
+			result.wins[side],
+			result.win_rate(side) * 100.0,
+			result.damage_dealt[side] as f64 / games
+		);
+	}
+	println!("draws: {}", result.draws);
+	println!("average turns: {:.1}", result.average_turns());
+
+	Ok(())
+}
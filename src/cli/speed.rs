@@ -0,0 +1,53 @@
+use crate::analysis;
+
+const USAGE: &str = "usage: speed <paste file>... [--all] [--rules <file>] [--convert-evs]
+lists every member of the given teams by action time tier; --all also shows empty tiers";
+
+pub fn speed_command(args: &[String]) -> Result<(), String>
+{
+	let files = super::positional(args, &["--rules"]);
+	if files.is_empty()
+	{
+		return Err(String::from(USAGE));
+	}
+
+	let teams = files
+		.iter()
+		.map(|file| super::read_team(file, args))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let rules = super::ruleset(args)?;
+	let show_empty = super::has_flag(args, "--all");
+	for tier in analysis::speed_tiers(teams.iter().flat_map(|it| &it.members), &rules)
+	{
+		if tier.entries.is_empty() && !show_empty
+		{
+			continue;
+		}
+
+		let range = match (tier.min_speed, tier.max_speed)
+		{
+			(Some(min), Some(max)) => format!("{min}-{max}"),
+			(Some(min), None) => format!("{min}+"),
+			(None, Some(max)) => format!("{max} or less"),
+			(None, None) => String::from("any"),
+		};
+		println!("action time {:>2} (speed {range})", tier.action_time);
+
+		for entry in tier.entries
+		{
+			let next = entry.to_next_tier.map_or_else(
+				|| String::from("fastest tier"),
+				|(speed, action_time)| format!("+{speed} for action time {action_time}"),
+			);
+			println!(
+				"  {:<20} Lv. {:<3} speed {:>3}  {next}",
+				entry.pokemon.name(),
+				entry.pokemon.level,
+				entry.speed
+			);
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::{
+	analysis::{self, StyleObjective},
+	data::BattlePokemon,
+	parsing, MOVE_MAP,
+};
+
+const USAGE: &str = "usage: style <attacking paste file> <defending paste file> <move> [options]
+	--attacker <n>         use member n of the attacking team (default 1)
+	--target <n>           use member n of the defending team (default 1)
+	--objective <goal>     damage, rate, accuracy, crit, effect or tempo (default rate)
+	--rules <file>         use a different ruleset
+	--convert-evs          read mainline EVs in pastes as effort levels";
+
+pub fn style_command(args: &[String]) -> Result<(), String>
+{
+	let [attacking, defending, move_name] =
+		super::positional(args, &["--attacker", "--target", "--objective", "--rules"])[..]
+	else
+	{
+		return Err(String::from(USAGE));
+	};
+	let attacking = super::read_team(attacking, args)?;
+	let defending = super::read_team(defending, args)?;
+	let rules = super::ruleset(args)?;
+	let attacker = BattlePokemon::new(
+		super::team_member(&attacking, super::parsed_flag(args, "--attacker")?)?,
+		&rules,
+	);
+	let target = BattlePokemon::new(
+		super::team_member(&defending, super::parsed_flag(args, "--target")?)?,
+		&rules,
+	);
+	let mv = MOVE_MAP
+		.get(&parsing::move_id_from(move_name))
+		.ok_or_else(|| format!("no move called '{move_name}'"))?;
+	let objective = super::parsed_flag(args, "--objective")?.unwrap_or(StyleObjective::DamageRate);
+
+	let advice = analysis::advise_style(&attacker, &target, mv, objective);
+	println!(
+		"{} using {} on {}",
+		attacker.pokemon.name(),
+		parsing::display_name_from(&mv.id),
+		target.pokemon.name()
+	);
+	println!(
+		"  {:<8} {:>9} {:>8} {:>6} {:>6} {:>7} {:>5} {:>5}",
+		"style", "damage", "average", "hit", "crit", "effect", "cost", "lead"
+	);
+	for option in &advice.options
+	{
+		println!(
+			"  {:<8} {:>9} {:>8.1} {:>5.1}% {:>5.1}% {:>7} {:>5} {:>5}{}",
+			format!("{:?}", option.style),
+			format!("{}-{}", option.min_damage, option.max_damage),
+			option.expected_damage,
+			option.hit_chance * 100.0,
+			option.crit_chance * 100.0,
+			option
+				.effect_chance
+				.map_or_else(|| String::from("-"), |it| format!("{:.0}%", it * 100.0)),
+			option.action_time_cost,
+			option.turn_lead,
+			if option.acts_again() { "  acts again" } else { "" }
+		);
+	}
+	println!("recommended for {objective:?}: {:?}", advice.recommended);
+
+	Ok(())
+}
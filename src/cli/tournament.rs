@@ -0,0 +1,188 @@
+use crate::{
+	battle::{self, Entrant, TournamentOptions, TournamentResult},
+	MOVE_MAP, STATUS_MAP,
+};
+
+const USAGE: &str = "usage: tournament <paste file>... [options]
+plays every agent with every team against each other and ranks them
+	--agents <a,b,..>     agents to enter, see battle (default greedy)
+	--games <n>           games between each pair of entrants (default 10)
+	--max-turns <n>       a game is a draw after this many actions (default 500)
+	--participants <n>    how many pokemon each side has out at once (default 1)
+	--doubles             the same as --participants 2
+	--seed <n>            seed for every battle and agent
+	--threads <n>         threads to play games on (default one per core)
+	--rules <file>        use a different ruleset
+	--json                print the leaderboard and matchups as json
+	--csv <dir>           write leaderboard.csv and matchups.csv to a directory
+	--convert-evs         read mainline EVs in pastes as effort levels";
+
+pub fn tournament_command(args: &[String]) -> Result<(), String>
+{
+	let value_flags = [
+		"--agents",
+		"--games",
+		"--max-turns",
+		"--participants",
+		"--seed",
+		"--threads",
+		"--rules",
+		"--csv",
+	];
+	let files = super::positional(args, &value_flags);
+	if files.is_empty()
+	{
+		return Err(String::from(USAGE));
+	}
+	let teams = files
+		.iter()
+		.map(|it| super::read_team(it, args))
+		.collect::<Result<Vec<_>, _>>()?;
+	let rules = super::ruleset(args)?;
+	let default_participants = if super::has_flag(args, "--doubles") { 2 } else { 1 };
+	let options = TournamentOptions {
+		games: super::parsed_flag(args, "--games")?.unwrap_or(10),
+		max_turns: super::parsed_flag(args, "--max-turns")?.unwrap_or(500),
+		participants: super::parsed_flag(args, "--participants")?.unwrap_or(default_participants),
+		seed: super::parsed_flag(args, "--seed")?,
+		threads: super::parsed_flag(args, "--threads")?.unwrap_or_else(battle::default_threads),
+	};
+
+	let agent_names = super::flag_value(args, "--agents")
+		.unwrap_or("greedy")
+		.split(',')
+		.map(|it| it.trim().to_owned())
+		.collect::<Vec<_>>();
+	let mut entrants = Vec::new();
+	for agent in &agent_names
+	{
+		// make one up front so a bad name is reported before anything is played
+		super::agent_from_name(agent, None)?;
+		for (file, team) in files.iter().zip(&teams)
+		{
+			let stem = std::path::Path::new(file)
+				.file_stem()
+				.map_or_else(|| file.to_string(), |it| it.to_string_lossy().into_owned());
+			let agent = agent.clone();
+			entrants.push(Entrant {
+				name: format!("{agent}@{stem}"),
+				team,
+				agent: Box::new(move |seed| {
+					super::agent_from_name(&agent, seed).expect("the name was checked already")
+				}),
+			});
+		}
+	}
+	if entrants.len() < 2
+	{
+		return Err(String::from("a tournament needs at least two entrants"));
+	}
+
+	let result = battle::run_tournament(&entrants, &STATUS_MAP, &MOVE_MAP, &rules, options)
+		.map_err(|err| err.to_string())?;
+
+	if super::has_flag(args, "--json")
+	{
+		let json = serde_json::to_string_pretty(&result).map_err(|err| err.to_string())?;
+		println!("{json}");
+	}
+	else if let Some(dir) = super::flag_value(args, "--csv")
+	{
+		write_csv(dir, &result)?;
+	}
+	else
+	{
+		print_tables(&result);
+	}
+
+	Ok(())
+}
+
+fn write_csv(dir: &str, result: &TournamentResult) -> Result<(), String>
+{
+	let mut leaderboard =
+		String::from("name,games,wins,losses,draws,elo,glicko,glicko_deviation\n");
+	leaderboard.extend(result.standings.iter().map(|it| {
+		format!(
+			"{},{},{},{},{},{:.1},{:.1},{:.1}\n",
+			it.name,
+			it.games,
+			it.wins,
+			it.losses,
+			it.draws,
+			it.elo.rating,
+			it.glicko.rating,
+			it.glicko.deviation
+		)
+	}));
+
+	let mut matchups = String::from(
+		"first,second,games,first_wins,second_wins,draws,average_turns,first_average_damage,second_average_damage\n",
+	);
+	matchups.extend(result.matchups.iter().map(|it| {
+		let (first_damage, second_damage) = it.average_damage_dealt();
+		format!(
+			"{},{},{},{},{},{},{:.1},{:.1},{:.1}\n",
+			it.first,
+			it.second,
+			it.games,
+			it.first_wins,
+			it.second_wins,
+			it.draws,
+			it.average_turns(),
+			first_damage,
+			second_damage
+		)
+	}));
+
+	let dir = std::path::Path::new(dir);
+	std::fs::create_dir_all(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+	for (name, contents) in [("leaderboard.csv", leaderboard), ("matchups.csv", matchups)]
+	{
+		let path = dir.join(name);
+		std::fs::write(&path, contents).map_err(|err| format!("{}: {err}", path.display()))?;
+	}
+
+	Ok(())
+}
+
+fn print_tables(result: &TournamentResult)
+{
+	println!(
+		"{:<28}{:>7}{:>7}{:>7}{:>7}{:>8}{:>8}{:>6}",
+		"entrant", "games", "wins", "losses", "draws", "elo", "glicko", "rd"
+	);
+	for it in &result.standings
+	{
+		println!(
+			"{:<28.28}{:>7}{:>7}{:>7}{:>7}{:>8.0}{:>8.0}{:>6.0}",
+			it.name,
+			it.games,
+			it.wins,
+			it.losses,
+			it.draws,
+			it.elo.rating,
+			it.glicko.rating,
+			it.glicko.deviation
+		);
+	}
+
+	println!();
+	println!(
+		"{:<28}{:<28}{:>10}{:>8}{:>10}{:>10}",
+		"first", "second", "w-l-d", "turns", "dmg 1st", "dmg 2nd"
+	);
+	for it in &result.matchups
+	{
+		let (first_damage, second_damage) = it.average_damage_dealt();
+		println!(
+			"{:<28.28}{:<28.28}{:>10}{:>8.1}{:>10.1}{:>10.1}",
+			it.first,
+			it.second,
+			format!("{}-{}-{}", it.first_wins, it.second_wins, it.draws),
+			it.average_turns(),
+			first_damage,
+			second_damage
+		);
+	}
+}
@@ -0,0 +1,39 @@
+const USAGE: &str = "usage: validate <paste file>... [--convert-evs]
+checks that every team follows the rules of the game";
+
+pub fn validate_command(args: &[String]) -> Result<(), String>
+{
+	let files = super::positional(args, &[]);
+	if files.is_empty()
+	{
+		return Err(String::from(USAGE));
+	}
+
+	let mut invalid = 0;
+	for file in files
+	{
+		let team = super::read_team(file, args)?;
+		let issues = team.validate();
+		if issues.is_empty()
+		{
+			println!("{file}: ok");
+			continue;
+		}
+
+		invalid += 1;
+		println!("{file}:");
+		for issue in issues
+		{
+			println!("  {issue}");
+		}
+	}
+
+	if invalid == 0
+	{
+		Ok(())
+	}
+	else
+	{
+		Err(format!("{invalid} team(s) are not valid"))
+	}
+}
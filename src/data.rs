@@ -1,16 +1,22 @@
+mod boss;
 mod moves;
 mod pokemon;
+mod ruleset;
 pub mod serialization;
 mod stats;
 mod status;
+mod team;
 mod types;
 
 use std::collections::HashMap;
 
+pub use boss::*;
 pub use moves::*;
 pub use pokemon::*;
+pub use ruleset::*;
 pub use stats::*;
 pub use status::*;
+pub use team::*;
 pub use types::*;
 
 pub trait Identifiable
@@ -19,3 +25,19 @@ pub trait Identifiable
 }
 
 pub type RegMap<T> = HashMap<Box<str>, T>;
+
+// the dex is shared between the threads that play batches of battles, so none of it may hold
+// anything that can't be
+const _: () = {
+	const fn assert_send_sync<T: Send + Sync>() {}
+	assert_send_sync::<RegMap<Type>>();
+	assert_send_sync::<TypeChart>();
+	assert_send_sync::<RegMap<Move>>();
+	assert_send_sync::<RegMap<Species>>();
+	assert_send_sync::<RegMap<StatusCondition>>();
+	assert_send_sync::<RegMap<Nature>>();
+	assert_send_sync::<Ruleset>();
+	assert_send_sync::<Team>();
+	assert_send_sync::<BattlePokemon>();
+	assert_send_sync::<AppliedStatus>();
+};
@@ -0,0 +1,90 @@
+/// How a noble battle differs from a trainer battle. The boss fights alone, hits harder and takes
+/// longer to bring down, and the battle is won and lost by its own conditions
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BossRules
+{
+	/// Scales the boss's max hp
+	pub hp_multiplier: f64,
+	/// Scales every other stat of the boss's
+	pub stat_multiplier: f64,
+	/// Move pools the boss moves on to as it loses hp, in order
+	#[serde(default)]
+	pub phases: Box<[BossPhase]>,
+	/// The challenger loses once this many of its pokemon have fainted, rather than all of them
+	#[serde(default)]
+	pub faints_allowed: Option<usize>,
+	/// The challenger loses if the boss is still standing after this many turns
+	#[serde(default)]
+	pub turn_limit: Option<u32>,
+}
+
+impl BossRules
+{
+	/// Checks the rules make sense before a battle is fought by them
+	pub fn validate(&self) -> Result<(), String>
+	{
+		for (name, multiplier) in
+			[("hp_multiplier", self.hp_multiplier), ("stat_multiplier", self.stat_multiplier)]
+		{
+			if !multiplier.is_finite() || multiplier <= 0.0
+			{
+				return Err(format!("{name} must be above 0, not {multiplier}"));
+			}
+		}
+		if let Some(index) = self.phases.iter().position(|it| it.moves.is_empty())
+		{
+			return Err(format!("phase {} has no moves to use", index + 1));
+		}
+
+		Ok(())
+	}
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BossPhase
+{
+	/// The phase starts once the boss is down to this percentage of its max hp or below
+	pub hp_percent: i32,
+	/// Ids of the moves the boss uses during the phase
+	pub moves: Box<[Box<str>]>,
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	fn rules() -> BossRules
+	{
+		BossRules {
+			hp_multiplier: 2.0,
+			stat_multiplier: 1.5,
+			phases: Box::new([BossPhase {
+				hp_percent: 50,
+				moves: Box::new([Box::from("tackle")]),
+			}]),
+			faints_allowed: None,
+			turn_limit: None,
+		}
+	}
+
+	#[test]
+	fn rejects_rules_a_boss_cannot_fight_by()
+	{
+		assert!(rules().validate().is_ok());
+
+		for multiplier in [0.0, -1.0, f64::NAN]
+		{
+			let mut hp = rules();
+			hp.hp_multiplier = multiplier;
+			assert!(hp.validate().is_err());
+			let mut stat = rules();
+			stat.stat_multiplier = multiplier;
+			assert!(stat.validate().is_err());
+		}
+
+		let mut moveless = rules();
+		moveless.phases[0].moves = Box::new([]);
+		assert!(moveless.validate().is_err());
+	}
+}
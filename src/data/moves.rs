@@ -1,9 +1,9 @@
 use crate::data;
 pub use style::*;
 
-use data::{Side, Type};
+use data::{BattlePokemon, Side, Type};
 
-#[derive(Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone)]
 pub struct Move<'a>
 {
 	pub id: Box<str>,
@@ -37,13 +37,33 @@ impl Category
 
 mod style
 {
-	#[derive(Debug, Clone, Copy)]
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+	#[serde(rename_all = "snake_case")]
 	pub enum Style
 	{
 		Regular,
 		Agile,
 		Strong,
 	}
+	impl Style
+	{
+		pub const ALL: [Self; 3] = [Self::Regular, Self::Agile, Self::Strong];
+	}
+	impl std::str::FromStr for Style
+	{
+		type Err = ();
+
+		fn from_str(s: &str) -> Result<Self, Self::Err>
+		{
+			match s.to_lowercase().as_str()
+			{
+				"regular" => Ok(Self::Regular),
+				"agile" => Ok(Self::Agile),
+				"strong" => Ok(Self::Strong),
+				_ => Err(()),
+			}
+		}
+	}
 
 	#[derive(Debug, Hash, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
 	pub struct StyleTriad<T>
@@ -169,6 +189,23 @@ pub enum MoveEffect
 	},
 }
 
+impl MoveEffect
+{
+	pub fn condition(&self) -> &MoveEffectCondition
+	{
+		match self
+		{
+			Self::Heal { condition, .. }
+			| Self::Recoil { condition, .. }
+			| Self::ApplyStatus { condition, .. }
+			| Self::CureStatus { condition, .. }
+			| Self::MultiplyPower { condition, .. }
+			| Self::ModifyData { condition, .. }
+			| Self::SwapOffenseAndDefense { condition, .. } => condition,
+		}
+	}
+}
+
 fn always() -> StyleTriad<i32>
 {
 	StyleTriad::all(100)
@@ -188,6 +225,12 @@ impl MoveEffectCondition
 	{
 		self.user.is_none() && self.target.is_none()
 	}
+
+	pub fn is_met(&self, user: &BattlePokemon, target: &BattlePokemon) -> bool
+	{
+		self.user.as_ref().map_or(true, |it| it.is_met(user))
+			&& self.target.as_ref().map_or(true, |it| it.is_met(target))
+	}
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
@@ -198,6 +241,20 @@ pub struct PokemonConditionData
 	#[serde(rename = "status", skip_serializing_if = "Option::is_none")]
 	pub status_ids: Option<Box<[Box<str>]>>,
 }
+impl PokemonConditionData
+{
+	/// Whether the pokemon is the right species and has any one of the statuses
+	pub fn is_met(&self, pokemon: &BattlePokemon) -> bool
+	{
+		self.species_id
+			.as_ref()
+			.map_or(true, |it| *it == pokemon.pokemon.species.id)
+			&& self
+				.status_ids
+				.as_ref()
+				.map_or(true, |ids| ids.iter().any(|it| pokemon.has_status(it)))
+	}
+}
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -1,8 +1,8 @@
 use crate::data;
 
 use data::{
-	AppliedStatus, Category, Effect, Move, Nature, Stat, StatBlock, StatusCondition, Style,
-	StyleTriad, Type, TypePair, Volatility,
+	AppliedStatus, Category, Effect, Move, Nature, Ruleset, Stat, StatBlock, StatusCondition, Style,
+	StyleTriad, Type, TypeSet, Volatility,
 };
 use rand::Rng;
 use std::collections::{HashMap, HashSet};
@@ -12,7 +12,14 @@ pub struct Species<'a>
 {
 	pub id: Box<str>,
 	pub base_stats: StatBlock,
-	pub types: TypePair<'a>,
+	pub types: TypeSet<'a>,
+}
+impl<'a> Species<'a>
+{
+	pub fn base_stat_total(&self) -> i32
+	{
+		self.base_stats.iter().map(|it| it.1).sum()
+	}
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
@@ -23,16 +30,23 @@ pub enum Side
 	Target,
 }
 
+/// How many of an alpha's stats are guaranteed a minimum effort level
+pub const ALPHA_GUARANTEED_STATS: usize = 3;
+pub const ALPHA_MIN_EFFORT_LEVEL: i32 = 3;
+
 #[derive(Debug)]
 pub struct Pokemon<'a>
 {
 	pub species: &'a Species<'a>,
 	pub nickname: Option<String>,
 	pub is_shiny: bool,
+	pub is_alpha: bool,
 	pub level: u8,
 	pub nature: Nature,
 	pub effort_levels: StatBlock,
 	pub moveset: HashSet<&'a Move<'a>>,
+	/// An extra move on top of the usual four, only allowed on alphas
+	pub alpha_move: Option<&'a Move<'a>>,
 }
 impl<'a> Pokemon<'a>
 {
@@ -42,10 +56,12 @@ impl<'a> Pokemon<'a>
 			species,
 			nickname: None,
 			is_shiny: false,
+			is_alpha: false,
 			level: 100,
 			nature: Nature::default(),
 			effort_levels: StatBlock::all(10),
 			moveset: HashSet::new(),
+			alpha_move: None,
 		}
 	}
 
@@ -59,9 +75,29 @@ impl<'a> Pokemon<'a>
 		StatBlock::generate(|stat| self.calculate_stat(stat))
 	}
 
-	fn calculate_stat(&self, stat: Stat) -> i32
+	/// Every move this pokemon can use, the alpha move included
+	pub fn moves(&self) -> impl Iterator<Item = &'a Move<'a>> + '_
+	{
+		self.moveset
+			.iter()
+			.copied()
+			.chain(self.alpha_move.filter(|it| !self.moveset.contains(it)))
+	}
+
+	/// Raises an alpha's effort levels to what alphas are guaranteed, see [`alpha_effort_levels`].
+	/// The setters do this already, it's only needed after changing the fields directly
+	pub fn apply_alpha_effort_levels(&mut self)
+	{
+		if self.is_alpha
+		{
+			self.effort_levels = alpha_effort_levels(&self.effort_levels);
+		}
+	}
+
+	pub fn calculate_stat(&self, stat: Stat) -> i32
 	{
 		let base = self.species.base_stats[stat];
+		let effort_level = self.effort_levels[stat];
 		if stat == Stat::Hp
 		{
 			// is this more readable? i think maybe ¯\_(ツ)_/¯
@@ -71,7 +107,7 @@ impl<'a> Pokemon<'a>
 					.mul_add(0.01, 1.0)
 					.mul_add(f64::from(base), f64::from(self.level))
 					.floor(),
-			) as i32 + data::effort_bonus(self.effort_levels[stat], self.level, base)
+			) as i32 + data::effort_bonus(effort_level, self.level, base)
 				.expect("effort level was not in range [0, 10]")
 		}
 		else
@@ -79,7 +115,7 @@ impl<'a> Pokemon<'a>
 			f64::floor(
 				(f64::from(self.level).mul_add(0.02, 1.0) * f64::from(base) / 1.5).floor()
 					* self.nature.multiplier(stat),
-			) as i32 + data::effort_bonus(self.effort_levels[stat], self.level, base)
+			) as i32 + data::effort_bonus(effort_level, self.level, base)
 				.expect("effort level was not in range [0, 10]")
 		}
 	}
@@ -94,6 +130,17 @@ impl<'a> Pokemon<'a>
 		self.is_shiny = is_shiny;
 		self
 	}
+	pub fn set_alpha(mut self, is_alpha: bool) -> Self
+	{
+		self.is_alpha = is_alpha;
+		self.apply_alpha_effort_levels();
+		self
+	}
+	pub fn set_alpha_move(mut self, alpha_move: Option<&'a Move<'a>>) -> Self
+	{
+		self.alpha_move = alpha_move;
+		self
+	}
 	pub fn set_level(mut self, level: u8) -> Self
 	{
 		self.level = level;
@@ -107,6 +154,7 @@ impl<'a> Pokemon<'a>
 	pub fn set_effort_levels(mut self, effort_levels: StatBlock) -> Self
 	{
 		self.effort_levels = effort_levels;
+		self.apply_alpha_effort_levels();
 		self
 	}
 	pub fn add_move(mut self, mv: &'a Move<'a>) -> Self
@@ -126,38 +174,180 @@ impl<'a> Pokemon<'a>
 		self
 	}
 
-	pub fn base_action_time(&self) -> i32
+	pub fn base_action_time(&self, rules: &Ruleset) -> i32
 	{
-		data::base_action_time(self.stats().spe)
+		rules.base_action_time(self.stats().spe)
 	}
 }
 
+/// `effort_levels` with the highest ones raised to at least `ALPHA_MIN_EFFORT_LEVEL`, as an alpha's
+/// are, with ties going to the stat that comes first
+pub fn alpha_effort_levels(effort_levels: &StatBlock) -> StatBlock
+{
+	let mut stats = Stat::ALL;
+	stats.sort_by_key(|it| std::cmp::Reverse(effort_levels[*it]));
+	let raised = &stats[..ALPHA_GUARANTEED_STATS];
+	StatBlock::generate(|stat| {
+		if raised.contains(&stat)
+		{
+			effort_levels[stat].max(ALPHA_MIN_EFFORT_LEVEL)
+		}
+		else
+		{
+			effort_levels[stat]
+		}
+	})
+}
+
+#[derive(Clone)]
 pub struct BattlePokemon<'a>
 {
 	pub pokemon: &'a Pokemon<'a>,
+	pub rules: &'a Ruleset,
 	damage: i32,
 	action_time: i32,
 	non_volatile_status: Option<AppliedStatus<'a>>,
 	volatile_statuses: HashMap<Box<str>, AppliedStatus<'a>>,
+	hp_multiplier: f64,
+	/// Applies to every stat but hp
+	stat_multiplier: f64,
+	/// Moves used in place of the pokemon's own
+	move_pool: Option<Box<[&'a Move<'a>]>>,
 }
 impl<'a> BattlePokemon<'a>
 {
-	pub fn new(pokemon: &'a Pokemon) -> Self
+	pub fn new(pokemon: &'a Pokemon, rules: &'a Ruleset) -> Self
 	{
 		Self {
 			pokemon,
+			rules,
 			damage: 0,
-			action_time: pokemon.base_action_time(),
+			action_time: pokemon.base_action_time(rules),
 			non_volatile_status: None,
 			volatile_statuses: HashMap::new(),
+			hp_multiplier: 1.0,
+			stat_multiplier: 1.0,
+			move_pool: None,
 		}
 	}
 
+	/// The pokemon as it was partway through a battle. Statuses are put back as they were, without
+	/// checking immunities like [`Self::apply_status`] does
+	pub fn restored<I>(
+		pokemon: &'a Pokemon,
+		rules: &'a Ruleset,
+		damage: i32,
+		action_time: i32,
+		statuses: I,
+	) -> Self
+	where
+		I: IntoIterator<Item = AppliedStatus<'a>>,
+	{
+		let mut restored = Self {
+			damage,
+			action_time,
+			..Self::new(pokemon, rules)
+		};
+		for status in statuses
+		{
+			match status.condition.volatility
+			{
+				Volatility::NonVolatile => restored.non_volatile_status = Some(status),
+				Volatility::Volatile =>
+				{
+					restored
+						.volatile_statuses
+						.insert(status.condition.id.clone(), status);
+				}
+			}
+		}
+
+		restored
+	}
+
+	/// Scales the pokemon's max hp and its other stats, like a noble pokemon's. Damage taken so far
+	/// stays the same
+	pub fn scale_stats(&mut self, hp_multiplier: f64, stat_multiplier: f64)
+	{
+		self.hp_multiplier = hp_multiplier;
+		self.stat_multiplier = stat_multiplier;
+	}
+
+	/// The moves the pokemon can use in battle, which are its own unless a move pool was set
+	pub fn moves(&self) -> impl Iterator<Item = &'a Move<'a>> + '_
+	{
+		let pool = self.move_pool.as_deref();
+		pool.into_iter()
+			.flatten()
+			.copied()
+			.chain(self.pokemon.moves().filter(move |_| pool.is_none()))
+	}
+
+	/// Gives the pokemon other moves to use, or its own back with `None`
+	pub fn set_move_pool(&mut self, moves: Option<Box<[&'a Move<'a>]>>)
+	{
+		self.move_pool = moves;
+	}
+
 	pub fn current_hp(&self) -> i32
 	{
 		self.effective_stats().hp - self.damage
 	}
 
+	pub fn max_hp(&self) -> i32
+	{
+		self.effective_stats().hp
+	}
+
+	/// Hp lost so far, which stays the same when stat changes move the maximum
+	pub fn damage(&self) -> i32
+	{
+		self.damage
+	}
+
+	pub fn is_fainted(&self) -> bool
+	{
+		self.current_hp() <= 0
+	}
+
+	/// Deals damage without going past 0 hp, returning how much was actually taken
+	pub fn take_damage(&mut self, amount: i32) -> i32
+	{
+		let taken = amount.clamp(0, self.current_hp().max(0));
+		self.damage += taken;
+		taken
+	}
+
+	/// Restores hp without going past the maximum, returning how much was actually restored
+	pub fn heal(&mut self, amount: i32) -> i32
+	{
+		let healed = amount.clamp(0, self.damage);
+		self.damage -= healed;
+		healed
+	}
+
+	pub fn has_status(&self, id: &str) -> bool
+	{
+		self.status_conditions().any(|it| &*it.condition.id == id)
+	}
+
+	/// Removes the status, returning whether there was one to remove
+	pub fn cure_status(&mut self, id: &str) -> bool
+	{
+		if self
+			.non_volatile_status
+			.as_ref()
+			.is_some_and(|it| &*it.condition.id == id)
+		{
+			self.non_volatile_status = None;
+			true
+		}
+		else
+		{
+			self.volatile_statuses.remove(id).is_some()
+		}
+	}
+
 	pub fn status_conditions(&self) -> impl Iterator<Item = &AppliedStatus>
 	{
 		std::iter::once(&self.non_volatile_status)
@@ -222,7 +412,16 @@ impl<'a> BattlePokemon<'a>
 
 	pub fn multiplier_to_stat(&self, st: Stat) -> f64
 	{
-		self.status_effects()
+		let scale = if st == Stat::Hp
+		{
+			self.hp_multiplier
+		}
+		else
+		{
+			self.stat_multiplier
+		};
+		let from_statuses = self
+			.status_effects()
 			.filter_map(|eff| {
 				if let Effect::ModifyStat { stat,multiplier,} = eff && *stat == st
 				{
@@ -233,10 +432,12 @@ impl<'a> BattlePokemon<'a>
 					None
 				}
 			})
-			.product()
+			.product::<f64>();
+
+		scale * from_statuses
 	}
 
-	pub fn types(&self) -> &TypePair
+	pub fn types(&self) -> &TypeSet
 	{
 		&self.pokemon.species.types
 	}
@@ -247,7 +448,47 @@ impl<'a> BattlePokemon<'a>
 
 	pub fn base_action_time(&self) -> i32
 	{
-		data::base_action_time(self.effective_stats().spe)
+		self.rules.base_action_time(self.effective_stats().spe)
+	}
+
+	pub fn action_time(&self) -> i32
+	{
+		self.action_time
+	}
+
+	pub fn set_action_time(&mut self, action_time: i32)
+	{
+		self.action_time = action_time;
+	}
+
+	pub fn add_action_time(&mut self, amount: i32)
+	{
+		self.action_time += amount;
+	}
+
+	/// A move used by this pokemon crits once in this many hits on average
+	pub fn crit_chance_denominator(&self, mv: &Move, style: Style) -> i32
+	{
+		self.rules.crit_denominator(
+			mv.crit_stage[style] + self.status_effects().map(Effect::crit_bonus).sum::<i32>(),
+		)
+	}
+
+	/// How much harder this pokemon is to hit, with accuracy divided by it
+	pub fn evasion_multiplier(&self) -> f64
+	{
+		self.status_effects()
+			.filter_map(|eff| {
+				if let Effect::EvasionModifier { multiplier } = eff
+				{
+					Some(multiplier)
+				}
+				else
+				{
+					None
+				}
+			})
+			.product()
 	}
 
 	pub fn calculate_damage(
@@ -266,31 +507,30 @@ impl<'a> BattlePokemon<'a>
 			style,
 		);
 
-		let crit_chance = match mv.crit_stage[style]
-			+ attacker
-				.status_effects()
-				.map(Effect::crit_bonus)
-				.sum::<i32>()
-		{
-			..=0 => 24,
-			1 => 8,
-			2 => 2,
-			3.. => 1,
-		};
-
-		let crit_multiplier: f64 = if rand::thread_rng().gen_range(0..crit_chance) == 0
+		let crit_multiplier: f64 = if rand::thread_rng()
+			.gen_range(0..attacker.crit_chance_denominator(mv, style))
+			== 0
 		{
-			1.5
+			attacker.rules.crit_multiplier
 		}
 		else
 		{
 			1.0
 		};
 
-		(f64::from(base_damage * rand::thread_rng().gen_range(85..100) / 100) * crit_multiplier)
+		(f64::from(Self::apply_roll(
+			base_damage,
+			rand::thread_rng().gen_range(attacker.rules.damage_rolls.clone()),
+		)) * crit_multiplier)
 			.floor() as i32
 	}
 
+	/// The damage dealt for one of the percentages in the ruleset's damage rolls, before crits
+	pub fn apply_roll(base_damage: i32, roll: i32) -> i32
+	{
+		base_damage * roll / 100
+	}
+
 	pub fn calculate_damage_no_roll(
 		attacker: &BattlePokemon,
 		target: &BattlePokemon,
@@ -326,7 +566,7 @@ impl<'a> BattlePokemon<'a>
 		let type_multiplier = target.types().damage_multiplier_from(move_type);
 		let stab_multiplier = if attacker.is_type(move_type)
 		{
-			1.25
+			attacker.rules.stab_multiplier
 		}
 		else
 		{
@@ -343,3 +583,61 @@ impl<'a> BattlePokemon<'a>
 		(f64::from(base_damage) * effects_multiplier * type_multiplier * stab_multiplier) as i32
 	}
 }
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{
+		data::{Team, TeamIssue},
+		SPECIES_MAP,
+	};
+
+	#[test]
+	fn raises_alpha_effort_levels_when_set()
+	{
+		let effort_levels = StatBlock {
+			hp: 0,
+			atk: 5,
+			def: 1,
+			spatk: 0,
+			spdef: 1,
+			spe: 0,
+		};
+		// whichever order the setters are called in, the stored effort levels end up raised
+		let before = Pokemon::new(&SPECIES_MAP["garchomp"])
+			.set_alpha(true)
+			.set_effort_levels(effort_levels.clone());
+		let after = Pokemon::new(&SPECIES_MAP["garchomp"])
+			.set_effort_levels(effort_levels.clone())
+			.set_alpha(true);
+
+		for pokemon in [before, after]
+		{
+			assert_eq!(pokemon.effort_levels.atk, 5);
+			assert_eq!(pokemon.effort_levels.def, 3);
+			assert_eq!(pokemon.effort_levels.spdef, 3);
+			assert_eq!(pokemon.effort_levels.hp, 0);
+			assert_eq!(pokemon.effort_levels.spe, 0);
+		}
+
+		let regular = Pokemon::new(&SPECIES_MAP["garchomp"]).set_effort_levels(effort_levels);
+		assert_eq!(regular.effort_levels.def, 1);
+	}
+
+	#[test]
+	fn calculates_stats_from_stored_effort_levels()
+	{
+		let mut pokemon =
+			Pokemon::new(&SPECIES_MAP["garchomp"]).set_effort_levels(StatBlock::all(0));
+		let stats = pokemon.stats();
+		pokemon.is_alpha = true;
+
+		assert_eq!(pokemon.stats().atk, stats.atk);
+		assert!(Team::new(String::from("Alpha"))
+			.add_member(pokemon)
+			.validate()
+			.iter()
+			.any(|it| matches!(it, TeamIssue::AlphaEffortLevelsTooLow(_))));
+	}
+}
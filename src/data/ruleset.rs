@@ -0,0 +1,179 @@
+use std::ops::Range;
+
+/// The numbers behind damage, crits and action time, so that house rules can be tried out
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Ruleset
+{
+	pub stab_multiplier: f64,
+	pub crit_multiplier: f64,
+	/// One in how many hits crit, by crit stage. Stages past the end use the last entry
+	pub crit_denominators: Box<[i32]>,
+	/// The percentages of the unrolled damage a hit can deal, each equally likely
+	pub damage_rolls: Range<i32>,
+	pub slowest_action_time: i32,
+	/// Faster tiers after slower ones
+	pub action_time_tiers: Box<[ActionTimeTier]>,
+	#[serde(default)]
+	pub spread: SpreadRules,
+}
+impl Ruleset
+{
+	/// Checks for numbers the calculations can't work with, since a ruleset file can say anything
+	pub fn validate(&self) -> Result<(), String>
+	{
+		if self.damage_rolls.is_empty()
+		{
+			return Err(format!(
+				"damage_rolls {}..{} has no rolls in it",
+				self.damage_rolls.start, self.damage_rolls.end
+			));
+		}
+		if self.crit_denominators.is_empty()
+		{
+			return Err(String::from("crit_denominators needs at least one denominator"));
+		}
+		if let Some(denominator) = self.crit_denominators.iter().find(|it| **it <= 0)
+		{
+			return Err(format!("crit_denominators must be above 0, not {denominator}"));
+		}
+		if let Some(tiers) = self
+			.action_time_tiers
+			.windows(2)
+			.find(|it| it[0].min_speed >= it[1].min_speed)
+		{
+			return Err(format!(
+				"action_time_tiers must go from slowest to fastest, not {} then {}",
+				tiers[0].min_speed, tiers[1].min_speed
+			));
+		}
+		if !self.spread.multiplier.is_finite() || self.spread.multiplier <= 0.0
+		{
+			return Err(format!(
+				"the spread multiplier must be above 0, not {}",
+				self.spread.multiplier
+			));
+		}
+
+		Ok(())
+	}
+
+	pub fn crit_denominator(&self, stage: i32) -> i32
+	{
+		let index = usize::try_from(stage).unwrap_or_default();
+		self.crit_denominators
+			.get(index)
+			.or_else(|| self.crit_denominators.last())
+			.copied()
+			.unwrap_or(1)
+	}
+
+	pub fn base_action_time(&self, speed: i32) -> i32
+	{
+		self.action_time_tiers
+			.iter()
+			.rev()
+			.find(|it| speed >= it.min_speed)
+			.map_or(self.slowest_action_time, |it| it.action_time)
+	}
+
+	/// The next faster action time tier, or `None` if already at the fastest
+	pub fn next_action_time_tier(&self, speed: i32) -> Option<&ActionTimeTier>
+	{
+		self.action_time_tiers.iter().find(|it| it.min_speed > speed)
+	}
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct ActionTimeTier
+{
+	pub min_speed: i32,
+	pub action_time: i32,
+}
+
+/// Which pokemon a spread move hits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpreadTarget
+{
+	Foes,
+	/// Every pokemon but the user, allies included
+	AllOthers,
+}
+
+/// Moves that hit more than one pokemon when several are out at once
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SpreadRules
+{
+	/// Damage each pokemon takes when a move hits more than one, as a fraction of the damage it
+	/// would have taken alone
+	pub multiplier: f64,
+	#[serde(default)]
+	pub foes: Box<[Box<str>]>,
+	#[serde(default)]
+	pub all: Box<[Box<str>]>,
+}
+impl SpreadRules
+{
+	/// Who the move spreads to, or `None` if it only hits its target
+	pub fn target_of(&self, move_id: &str) -> Option<SpreadTarget>
+	{
+		if self.foes.iter().any(|it| **it == *move_id)
+		{
+			Some(SpreadTarget::Foes)
+		}
+		else if self.all.iter().any(|it| **it == *move_id)
+		{
+			Some(SpreadTarget::AllOthers)
+		}
+		else
+		{
+			None
+		}
+	}
+}
+impl Default for SpreadRules
+{
+	fn default() -> Self
+	{
+		Self {
+			multiplier: 0.75,
+			foes: Box::default(),
+			all: Box::default(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use crate::RULESET;
+
+	#[test]
+	fn accepts_the_standard_rules()
+	{
+		assert!(RULESET.validate().is_ok());
+	}
+
+	#[test]
+	fn rejects_rules_the_calculations_cannot_use()
+	{
+		let mut rules = RULESET.clone();
+		rules.damage_rolls = 100..100;
+		assert!(rules.validate().is_err());
+
+		let mut rules = RULESET.clone();
+		rules.crit_denominators = Box::new([24, 0]);
+		assert!(rules.validate().is_err());
+
+		let mut rules = RULESET.clone();
+		rules.crit_denominators = Box::new([]);
+		assert!(rules.validate().is_err());
+
+		let mut rules = RULESET.clone();
+		rules.action_time_tiers.swap(0, 1);
+		assert!(rules.validate().is_err());
+
+		let mut rules = RULESET.clone();
+		rules.spread.multiplier = -0.5;
+		assert!(rules.validate().is_err());
+	}
+}
@@ -1,10 +1,14 @@
+mod battle;
 mod moves;
 mod species;
 mod status;
+mod team;
 
+pub use battle::*;
 pub use moves::*;
 pub use species::*;
 pub use status::*;
+pub use team::*;
 
 fn empty_slice<T>() -> Box<[T]>
 {
@@ -0,0 +1,96 @@
+use crate::data::{
+	serialization::MissingIdError, AppliedStatus, BattlePokemon, Move, Pokemon, RegMap, Ruleset,
+	StatusCondition,
+};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SerAppliedStatus
+{
+	pub status: Box<str>,
+	pub duration: i32,
+	pub source_move: Box<str>,
+}
+impl SerAppliedStatus
+{
+	pub fn into_applied_status<'a>(
+		self,
+		status_map: &'a RegMap<StatusCondition<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+	) -> Result<AppliedStatus<'a>, MissingIdError>
+	{
+		Ok(AppliedStatus {
+			condition: status_map
+				.get(&self.status)
+				.ok_or(MissingIdError(self.status))?,
+			duration: self.duration,
+			source_move: move_map
+				.get(&self.source_move)
+				.ok_or(MissingIdError(self.source_move))?,
+		})
+	}
+}
+impl From<&AppliedStatus<'_>> for SerAppliedStatus
+{
+	fn from(value: &AppliedStatus) -> Self
+	{
+		Self {
+			status: value.condition.id.clone(),
+			duration: value.duration,
+			source_move: value.source_move.id.clone(),
+		}
+	}
+}
+
+/// What a pokemon has been through in a battle. The pokemon itself is saved with its team
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SerBattlePokemon
+{
+	pub damage: i32,
+	pub action_time: i32,
+	#[serde(default = "super::empty_slice", skip_serializing_if = "<[_]>::is_empty")]
+	pub statuses: Box<[SerAppliedStatus]>,
+}
+impl SerBattlePokemon
+{
+	pub fn into_battle_pokemon<'a>(
+		self,
+		pokemon: &'a Pokemon<'a>,
+		rules: &'a Ruleset,
+		status_map: &'a RegMap<StatusCondition<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+	) -> Result<BattlePokemon<'a>, MissingIdError>
+	{
+		let statuses = self
+			.statuses
+			.into_vec()
+			.into_iter()
+			.map(|it| it.into_applied_status(status_map, move_map))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(BattlePokemon::restored(
+			pokemon,
+			rules,
+			self.damage,
+			self.action_time,
+			statuses,
+		))
+	}
+}
+impl From<&BattlePokemon<'_>> for SerBattlePokemon
+{
+	fn from(value: &BattlePokemon) -> Self
+	{
+		let mut statuses = value
+			.status_conditions()
+			.map(SerAppliedStatus::from)
+			.collect::<Box<[_]>>();
+		// volatile statuses come out in no particular order
+		statuses.sort_by(|a, b| a.status.cmp(&b.status));
+
+		Self {
+			damage: value.damage(),
+			action_time: value.action_time(),
+			statuses,
+		}
+	}
+}
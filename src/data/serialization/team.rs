@@ -0,0 +1,114 @@
+use crate::data::{Move, Nature, Pokemon, RegMap, Species, StatBlock, Team};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SerPokemon
+{
+	pub species: Box<str>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub nickname: Option<String>,
+	#[serde(default)]
+	pub is_shiny: bool,
+	#[serde(default)]
+	pub is_alpha: bool,
+	pub level: u8,
+	pub nature: Nature,
+	pub effort_levels: StatBlock,
+	pub moves: Box<[Box<str>]>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub alpha_move: Option<Box<str>>,
+}
+impl SerPokemon
+{
+	pub fn into_pokemon<'a>(
+		self,
+		species_map: &'a RegMap<Species<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+	) -> Result<Pokemon<'a>, MissingIdError>
+	{
+		let species = species_map
+			.get(&self.species)
+			.ok_or_else(|| MissingIdError(self.species.clone()))?;
+		let moves = self
+			.moves
+			.iter()
+			.map(|id| move_map.get(id).ok_or_else(|| MissingIdError(id.clone())))
+			.collect::<Result<Vec<_>, _>>()?;
+		let alpha_move = self
+			.alpha_move
+			.map(|id| move_map.get(&id).ok_or(MissingIdError(id)))
+			.transpose()?;
+
+		Ok(Pokemon::new(species)
+			.set_nickname(self.nickname)
+			.set_shiny(self.is_shiny)
+			.set_alpha(self.is_alpha)
+			.set_level(self.level)
+			.set_nature(self.nature)
+			.set_effort_levels(self.effort_levels)
+			.add_moves(moves)
+			.set_alpha_move(alpha_move))
+	}
+}
+impl From<&Pokemon<'_>> for SerPokemon
+{
+	fn from(value: &Pokemon) -> Self
+	{
+		let mut moves = value
+			.moveset
+			.iter()
+			.map(|it| it.id.clone())
+			.collect::<Box<[_]>>();
+		moves.sort();
+
+		Self {
+			species: value.species.id.clone(),
+			nickname: value.nickname.clone(),
+			is_shiny: value.is_shiny,
+			is_alpha: value.is_alpha,
+			level: value.level,
+			nature: value.nature,
+			effort_levels: value.effort_levels.clone(),
+			moves,
+			alpha_move: value.alpha_move.map(|it| it.id.clone()),
+		}
+	}
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SerTeam
+{
+	pub name: String,
+	pub members: Box<[SerPokemon]>,
+}
+impl SerTeam
+{
+	pub fn into_team<'a>(
+		self,
+		species_map: &'a RegMap<Species<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+	) -> Result<Team<'a>, MissingIdError>
+	{
+		Ok(Team {
+			name: self.name,
+			members: self
+				.members
+				.into_vec()
+				.into_iter()
+				.map(|it| it.into_pokemon(species_map, move_map))
+				.collect::<Result<_, _>>()?,
+		})
+	}
+}
+impl From<&Team<'_>> for SerTeam
+{
+	fn from(value: &Team) -> Self
+	{
+		Self {
+			name: value.name.clone(),
+			members: value.members.iter().map(SerPokemon::from).collect(),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub struct MissingIdError(pub Box<str>);
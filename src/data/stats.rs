@@ -1,22 +1,5 @@
 use std::str::FromStr;
 
-pub fn base_action_time(speed: i32) -> i32
-{
-	match speed
-	{
-		..=15 => 14,
-		16..=31 => 13,
-		32..=55 => 12,
-		56..=88 => 11,
-		89..=129 => 10,
-		130..=181 => 9,
-		182..=242 => 8,
-		243..=316 => 7,
-		317..=401 => 6,
-		402.. => 5,
-	}
-}
-
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Stat
@@ -28,6 +11,17 @@ pub enum Stat
 	SpDef,
 	Spe,
 }
+impl Stat
+{
+	pub const ALL: [Self; 6] = [
+		Self::Hp,
+		Self::Atk,
+		Self::Def,
+		Self::SpAtk,
+		Self::SpDef,
+		Self::Spe,
+	];
+}
 impl FromStr for Stat
 {
 	type Err = ParseStatError;
@@ -48,7 +42,7 @@ impl FromStr for Stat
 	}
 }
 
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Nature
 {
 	pub increased: Stat,
@@ -145,9 +139,9 @@ impl StatBlock
 		}
 	}
 
-	pub fn generate<F>(generator: F) -> Self
+	pub fn generate<F>(mut generator: F) -> Self
 	where
-		F: Fn(Stat) -> i32,
+		F: FnMut(Stat) -> i32,
 	{
 		Self {
 			hp: generator(Stat::Hp),
@@ -159,6 +153,11 @@ impl StatBlock
 		}
 	}
 
+	pub fn iter(&self) -> impl Iterator<Item = (Stat, i32)> + '_
+	{
+		Stat::ALL.into_iter().map(|stat| (stat, self[stat]))
+	}
+
 	// there has to be a better name for this but i cant come up with it -morgan 2023-12-15
 	pub fn generate_map<T, F, G>(self, generator: G, transform: F) -> Self
 	where
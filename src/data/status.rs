@@ -11,6 +11,21 @@ pub struct StatusCondition<'a>
 	pub effects: Box<[Effect]>,
 }
 
+impl StatusCondition<'_>
+{
+	/// 1 if the status does the pokemon it's on more good than harm, -1 if it does more harm, or
+	/// 0 if it evens out
+	pub fn helpfulness(&self) -> i32
+	{
+		self.effects
+			.iter()
+			.map(Effect::helpfulness)
+			.sum::<i32>()
+			.signum()
+	}
+}
+
+#[derive(Clone)]
 pub struct AppliedStatus<'a>
 {
 	pub condition: &'a StatusCondition<'a>,
@@ -94,6 +109,35 @@ impl Effect
 		}
 	}
 
+	/// 1 if the effect helps the pokemon it's on, -1 if it hurts it, or 0 if it does neither
+	pub fn helpfulness(&self) -> i32
+	{
+		let raises = |multiplier: f64| i32::from(multiplier > 1.0) - i32::from(multiplier < 1.0);
+		match self
+		{
+			Self::ModifyStat { multiplier, .. } | Self::EvasionModifier { multiplier } =>
+			{
+				raises(*multiplier)
+			}
+			Self::DamageMultiplier {
+				side: Side::User,
+				multiplier,
+				..
+			} => raises(*multiplier),
+			// taking more damage is worse
+			Self::DamageMultiplier {
+				side: Side::Target,
+				multiplier,
+				..
+			} => -raises(*multiplier),
+			Self::ModifyCritChance { stages } => stages.signum(),
+			Self::CancelTurn { .. }
+			| Self::TurnEndDamageFraction { .. }
+			| Self::TurnEndDamageMove { .. } => -1,
+			Self::SwapStats { .. } => 0,
+		}
+	}
+
 	pub fn crit_bonus(&self) -> i32
 	{
 		if let Self::ModifyCritChance { stages } = self
@@ -120,3 +164,36 @@ fn category_is_all(cat: &Category) -> bool
 {
 	*cat == Category::All
 }
+
+#[cfg(test)]
+mod tests
+{
+	use crate::STATUS_MAP;
+
+	#[test]
+	fn tells_buffs_from_ailments()
+	{
+		for id in ["power_boost", "guard_boost", "crit_boost", "primed", "obscured"]
+		{
+			assert_eq!(STATUS_MAP[id].helpfulness(), 1, "{id}");
+		}
+		for id in [
+			"burn",
+			"frostbite",
+			"poison",
+			"paralysis",
+			"drowsy",
+			"power_drop",
+			"guard_drop",
+			"slow_start",
+			"splinters",
+		]
+		{
+			assert_eq!(STATUS_MAP[id].helpfulness(), -1, "{id}");
+		}
+		for id in ["fixated", "stance_swap"]
+		{
+			assert_eq!(STATUS_MAP[id].helpfulness(), 0, "{id}");
+		}
+	}
+}
@@ -0,0 +1,142 @@
+use crate::data;
+
+use data::{Pokemon, Stat, Type, ALPHA_GUARANTEED_STATS, ALPHA_MIN_EFFORT_LEVEL};
+
+pub const MAX_TEAM_SIZE: usize = 6;
+pub const MAX_MOVES: usize = 4;
+
+#[derive(Debug)]
+pub struct Team<'a>
+{
+	pub name: String,
+	pub members: Vec<Pokemon<'a>>,
+}
+impl<'a> Team<'a>
+{
+	pub fn new(name: String) -> Self
+	{
+		Self {
+			name,
+			members: Vec::new(),
+		}
+	}
+
+	pub fn add_member(mut self, pokemon: Pokemon<'a>) -> Self
+	{
+		self.members.push(pokemon);
+		self
+	}
+
+	pub fn has_species(&self, species_id: &str) -> bool
+	{
+		self.members
+			.iter()
+			.any(|it| &*it.species.id == species_id)
+	}
+
+	pub fn has_move(&self, move_id: &str) -> bool
+	{
+		self.members
+			.iter()
+			.any(|it| it.moves().any(|mv| &*mv.id == move_id))
+	}
+
+	pub fn has_type(&self, typ: &Type) -> bool
+	{
+		self.members
+			.iter()
+			.any(|it| it.species.types.contains(typ))
+	}
+
+	/// Everything about the team that breaks the rules of the game
+	pub fn validate(&self) -> Vec<TeamIssue>
+	{
+		let mut issues = Vec::new();
+		if self.members.len() > MAX_TEAM_SIZE
+		{
+			issues.push(TeamIssue::TooManyMembers(self.members.len()));
+		}
+
+		for pokemon in &self.members
+		{
+			let name = || String::from(pokemon.name());
+			if !(1..=100).contains(&pokemon.level)
+			{
+				issues.push(TeamIssue::LevelOutOfRange(name(), pokemon.level));
+			}
+			for (stat, level) in pokemon.effort_levels.iter()
+			{
+				if !(0..=10).contains(&level)
+				{
+					issues.push(TeamIssue::EffortLevelOutOfRange(name(), stat, level));
+				}
+			}
+			if pokemon.moveset.len() > MAX_MOVES
+			{
+				issues.push(TeamIssue::TooManyMoves(name(), pokemon.moveset.len()));
+			}
+			if pokemon.alpha_move.is_some() && !pokemon.is_alpha
+			{
+				issues.push(TeamIssue::AlphaMoveWithoutAlpha(name()));
+			}
+			if pokemon.is_alpha
+			{
+				let raised = data::alpha_effort_levels(&pokemon.effort_levels);
+				if pokemon
+					.effort_levels
+					.iter()
+					.any(|(stat, level)| level != raised[stat])
+				{
+					issues.push(TeamIssue::AlphaEffortLevelsTooLow(name()));
+				}
+			}
+		}
+
+		issues
+	}
+}
+
+#[derive(Debug, Clone)]
+pub enum TeamIssue
+{
+	TooManyMembers(usize),
+	LevelOutOfRange(String, u8),
+	EffortLevelOutOfRange(String, Stat, i32),
+	TooManyMoves(String, usize),
+	AlphaMoveWithoutAlpha(String),
+	AlphaEffortLevelsTooLow(String),
+}
+impl std::fmt::Display for TeamIssue
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			Self::TooManyMembers(count) =>
+			{
+				write!(f, "has {count} members, only {MAX_TEAM_SIZE} are allowed")
+			}
+			Self::LevelOutOfRange(name, level) =>
+			{
+				write!(f, "{name} is level {level}, levels go from 1 to 100")
+			}
+			Self::EffortLevelOutOfRange(name, stat, level) => write!(
+				f,
+				"{name} has an effort level of {level} in {stat:?}, effort levels go from 0 to 10"
+			),
+			Self::TooManyMoves(name, count) =>
+			{
+				write!(f, "{name} knows {count} moves, only {MAX_MOVES} are allowed")
+			}
+			Self::AlphaMoveWithoutAlpha(name) =>
+			{
+				write!(f, "{name} has an alpha move but is not an alpha")
+			}
+			Self::AlphaEffortLevelsTooLow(name) => write!(
+				f,
+				"{name} is an alpha, so its {ALPHA_GUARANTEED_STATS} highest effort levels should be \
+				 at least {ALPHA_MIN_EFFORT_LEVEL}"
+			),
+		}
+	}
+}
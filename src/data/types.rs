@@ -1,6 +1,6 @@
 use crate::data;
 
-use data::Identifiable;
+use data::{Identifiable, RegMap};
 
 #[derive(Debug, Hash, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Type
@@ -12,6 +12,9 @@ pub struct Type
 	pub resistance_ids: Box<[Box<str>]>,
 	#[serde(rename = "immunities")]
 	pub immunity_ids: Box<[Box<str>]>,
+	/// Position of this type in a `TypeChart`, given out by `index_types`
+	#[serde(skip)]
+	pub index: Option<usize>,
 }
 impl Identifiable for Type
 {
@@ -41,93 +44,197 @@ impl Type
 			WeaknessLevel::Neutral
 		}
 	}
-}
 
-#[derive(Debug)]
-pub struct TypePair<'a>(pub &'a Type, pub Option<&'a Type>);
-impl<'a> TypePair<'a>
-{
-	pub fn contains(&self, typ: &Type) -> bool
+	fn chart_index(&self) -> usize
 	{
-		self.0.id == typ.id || self.1.is_some_and(|t| t.id == typ.id)
+		self.index
+			.unwrap_or_else(|| panic!("type '{}' was never numbered by index_types", self.id))
 	}
+}
 
-	pub fn damage_multiplier_from(&self, typ: &Type) -> f64
+/// Numbers every type in order of id so they can be looked up in a `TypeChart`
+pub fn index_types(mut type_map: RegMap<Type>) -> RegMap<Type>
+{
+	let mut ids = type_map.keys().cloned().collect::<Vec<_>>();
+	ids.sort();
+	for (index, id) in ids.iter().enumerate()
 	{
-		match self.0.weakness_to(typ) + self.1.map(|t| t.weakness_to(typ)).unwrap_or_default()
+		if let Some(typ) = type_map.get_mut(id)
 		{
-			WeaknessLevel::Immunity => 0.0,
-			WeaknessLevel::DoubleResist => 0.4,
-			WeaknessLevel::Resist => 0.5,
-			WeaknessLevel::Neutral => 1.0,
-			WeaknessLevel::Weak => 2.0,
-			WeaknessLevel::DoubleWeak => 2.5,
+			typ.index = Some(index);
 		}
 	}
+
+	type_map
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub enum WeaknessLevel
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TypeMultipliers
 {
-	Immunity,
-	DoubleResist,
-	Resist,
-	Neutral,
-	Weak,
-	DoubleWeak,
+	pub immune: f64,
+	pub neutral: f64,
+	pub weak: Box<[f64]>,
+	pub resist: Box<[f64]>,
+}
+
+/// Every attacking type against every defending type, worked out once up front
+#[derive(Debug)]
+pub struct TypeChart
+{
+	size: usize,
+	/// Indexed by attacking type, then defending type
+	relations: Box<[WeaknessLevel]>,
+	multipliers: TypeMultipliers,
 }
-// this feels more like multipliction. we'll see what we can do about that one -morgan 2023-12-12
-impl std::ops::Add<Self> for WeaknessLevel
+impl TypeChart
 {
-	type Output = Self;
+	/// The types must have been numbered by `index_types`
+	pub fn new(type_map: &RegMap<Type>, multipliers: TypeMultipliers) -> Self
+	{
+		let size = type_map.len();
+		let mut relations = vec![WeaknessLevel::Neutral; size * size].into_boxed_slice();
+		for attacking in type_map.values()
+		{
+			for defending in type_map.values()
+			{
+				relations[attacking.chart_index() * size + defending.chart_index()] =
+					defending.weakness_to(attacking);
+			}
+		}
+
+		Self {
+			size,
+			relations,
+			multipliers,
+		}
+	}
+
+	pub fn relation(&self, attacking: &Type, defending: &Type) -> WeaknessLevel
+	{
+		self.relations[attacking.chart_index() * self.size + defending.chart_index()]
+	}
 
-	fn add(self, rhs: Self) -> Self::Output
+	pub fn multiplier(&self, attacking: &Type, defending: &[&Type]) -> f64
 	{
-		if self == Self::Immunity || rhs == Self::Immunity
+		let mut steps = 0_i32;
+		for typ in defending
 		{
-			Self::Immunity
+			match self.relation(attacking, typ)
+			{
+				WeaknessLevel::Immunity => return self.multipliers.immune,
+				WeaknessLevel::Resist => steps -= 1,
+				WeaknessLevel::Neutral => (),
+				WeaknessLevel::Weak => steps += 1,
+			}
 		}
-		else
+
+		let from_list = |list: &[f64]| {
+			list.get(steps.unsigned_abs() as usize - 1)
+				.or_else(|| list.last())
+				.copied()
+				.unwrap_or(self.multipliers.neutral)
+		};
+		match steps
 		{
-			(i32::try_from(self).unwrap() + i32::try_from(rhs).unwrap()).into()
+			0 => self.multipliers.neutral,
+			1.. => from_list(&self.multipliers.weak),
+			..=-1 => from_list(&self.multipliers.resist),
 		}
 	}
 }
-impl Default for WeaknessLevel
+
+/// The types a pokemon has, however many that is
+#[derive(Debug)]
+pub struct TypeSet<'a>
 {
-	fn default() -> Self
-	{
-		Self::Neutral
-	}
+	types: Box<[&'a Type]>,
+	chart: &'a TypeChart,
 }
-impl From<i32> for WeaknessLevel
+impl<'a> TypeSet<'a>
 {
-	fn from(value: i32) -> Self
+	pub fn new(types: Box<[&'a Type]>, chart: &'a TypeChart) -> Self
 	{
-		match value
-		{
-			..=-2 => Self::DoubleResist,
-			-1 => Self::Resist,
-			0 => Self::Neutral,
-			1 => Self::Weak,
-			2.. => Self::DoubleWeak,
-		}
+		Self { types, chart }
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &'a Type> + '_
+	{
+		self.types.iter().copied()
+	}
+
+	pub fn contains(&self, typ: &Type) -> bool
+	{
+		self.types.iter().any(|it| it.id == typ.id)
 	}
+
+	pub fn damage_multiplier_from(&self, typ: &Type) -> f64
+	{
+		self.chart.multiplier(typ, &self.types)
+	}
+}
+
+/// How one type on its own takes hits from another
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum WeaknessLevel
+{
+	Immunity,
+	Resist,
+	#[default]
+	Neutral,
+	Weak,
 }
-impl TryFrom<WeaknessLevel> for i32
+
+#[cfg(test)]
+mod tests
 {
-	type Error = ();
+	use super::*;
+	use crate::{TYPE_CHART, TYPE_MAP};
 
-	fn try_from(value: WeaknessLevel) -> Result<Self, ()>
+	fn assert_multiplier(chart: &TypeChart, attacking: &str, defending: &[&str], expected: f64)
 	{
-		match value
-		{
-			WeaknessLevel::Immunity => Err(()),
-			WeaknessLevel::DoubleResist => Ok(-2),
-			WeaknessLevel::Resist => Ok(-1),
-			WeaknessLevel::Neutral => Ok(0),
-			WeaknessLevel::Weak => Ok(1),
-			WeaknessLevel::DoubleWeak => Ok(2),
-		}
+		let defending = defending.iter().map(|it| &TYPE_MAP[*it]).collect::<Vec<_>>();
+		let multiplier = chart.multiplier(&TYPE_MAP[attacking], &defending);
+		assert!((multiplier - expected).abs() < 1e-9, "{multiplier} is not {expected}");
+	}
+
+	#[test]
+	fn matches_the_standard_multipliers()
+	{
+		let chart = &*TYPE_CHART;
+		assert_multiplier(chart, "electric", &["ground"], 0.0);
+		assert_multiplier(chart, "electric", &["water", "ground"], 0.0);
+		assert_multiplier(chart, "normal", &["fire"], 1.0);
+		assert_multiplier(chart, "fire", &["grass", "water"], 1.0);
+		assert_multiplier(chart, "water", &["fire"], 2.0);
+		assert_multiplier(chart, "water", &["ground", "rock"], 2.5);
+		assert_multiplier(chart, "fire", &["water"], 0.5);
+		assert_multiplier(chart, "fire", &["water", "dragon"], 0.4);
+	}
+
+	#[test]
+	fn takes_multipliers_past_the_end_of_a_list_from_its_last()
+	{
+		let chart = TypeChart::new(
+			&TYPE_MAP,
+			TypeMultipliers {
+				immune: 0.0,
+				neutral: 1.0,
+				weak: Box::new([2.0]),
+				resist: Box::new([0.5]),
+			},
+		);
+		assert_multiplier(&chart, "water", &["ground", "rock"], 2.0);
+		assert_multiplier(&chart, "fire", &["water", "dragon"], 0.5);
+	}
+
+	#[test]
+	#[should_panic(expected = "never numbered")]
+	fn refuses_types_that_were_never_indexed()
+	{
+		let typ = toml::from_str::<Type>(
+			"id = \"unknown\"\nweaknesses = []\nresistances = []\nimmunities = []",
+		)
+		.unwrap();
+		TypeChart::new(&RegMap::from([(typ.id.clone(), typ)]), TYPE_CHART.multipliers.clone());
 	}
 }
@@ -0,0 +1,313 @@
+use crate::data;
+
+use data::{Move, Nature, Pokemon, RegMap, Species, StatBlock, Team};
+use rand::{
+	distributions::{Distribution, WeightedIndex},
+	rngs::StdRng,
+	seq::SliceRandom,
+	Rng, SeedableRng,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub enum LevelScaling
+{
+	Fixed(u8),
+	/// Weaker species get higher levels, linearly between these bounds by base stat total
+	BaseStatTotal
+	{
+		min_level: u8, max_level: u8
+	},
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GeneratorOptions
+{
+	pub seed: Option<u64>,
+	pub level_scaling: LevelScaling,
+	/// How much more likely a damaging move that gets STAB is to be picked
+	pub stab_weight: f64,
+	/// How much more likely a damaging move of a type the set can't already hit with is to be picked
+	pub coverage_weight: f64,
+}
+impl Default for GeneratorOptions
+{
+	fn default() -> Self
+	{
+		Self {
+			seed: None,
+			level_scaling: LevelScaling::Fixed(100),
+			stab_weight: 12.0,
+			coverage_weight: 2.0,
+		}
+	}
+}
+
+impl GeneratorOptions
+{
+	/// Checks that the options make sense, since the generator takes them as they are
+	pub fn validate(&self) -> Result<(), String>
+	{
+		let levels = match self.level_scaling
+		{
+			LevelScaling::Fixed(level) => [level, level],
+			LevelScaling::BaseStatTotal {
+				min_level,
+				max_level,
+			} =>
+			{
+				if min_level > max_level
+				{
+					return Err(format!(
+						"the lowest level ({min_level}) is higher than the highest ({max_level})"
+					));
+				}
+				[min_level, max_level]
+			}
+		};
+		if let Some(level) = levels.into_iter().find(|it| !(1..=100).contains(it))
+		{
+			return Err(format!("level {level} is not between 1 and 100"));
+		}
+
+		for (name, weight) in [("stab", self.stab_weight), ("coverage", self.coverage_weight)]
+		{
+			if !weight.is_finite() || weight < 0.0
+			{
+				return Err(format!("the {name} weight must be zero or more, not {weight}"));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+pub struct Generator<'a>
+{
+	rng: StdRng,
+	options: GeneratorOptions,
+	// sorted so that the same seed always gives the same sets, whatever order the maps iterate in
+	species: Vec<&'a Species<'a>>,
+	moves: Vec<&'a Move<'a>>,
+	natures: Vec<Nature>,
+	base_stat_total_range: (i32, i32),
+}
+impl<'a> Generator<'a>
+{
+	pub fn new(
+		species_map: &'a RegMap<Species<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+		nature_map: &RegMap<Nature>,
+		options: GeneratorOptions,
+	) -> Self
+	{
+		let mut species = species_map.values().collect::<Vec<_>>();
+		species.sort_by(|a, b| a.id.cmp(&b.id));
+
+		let mut moves = move_map
+			.values()
+			.filter(|it| &*it.id != "struggle")
+			.collect::<Vec<_>>();
+		moves.sort_by(|a, b| a.id.cmp(&b.id));
+
+		let mut natures = nature_map.iter().collect::<Vec<_>>();
+		natures.sort_by(|a, b| a.0.cmp(b.0));
+
+		let totals = species.iter().map(|it| it.base_stat_total());
+		let base_stat_total_range = (
+			totals.clone().min().unwrap_or_default(),
+			totals.max().unwrap_or_default(),
+		);
+
+		Self {
+			rng: options
+				.seed
+				.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+			options,
+			species,
+			moves,
+			natures: natures.into_iter().map(|it| *it.1).collect(),
+			base_stat_total_range,
+		}
+	}
+
+	pub fn team(&mut self, size: usize) -> Team<'a>
+	{
+		let species = self
+			.species
+			.choose_multiple(&mut self.rng, size)
+			.copied()
+			.collect::<Vec<_>>();
+
+		Team {
+			name: String::from("Random"),
+			members: species.into_iter().map(|it| self.pokemon_of(it)).collect(),
+		}
+	}
+
+	pub fn pokemon(&mut self) -> Pokemon<'a>
+	{
+		let species = self.species[self.rng.gen_range(0..self.species.len())];
+		self.pokemon_of(species)
+	}
+
+	pub fn pokemon_of(&mut self, species: &'a Species<'a>) -> Pokemon<'a>
+	{
+		let nature = self.natures[self.rng.gen_range(0..self.natures.len())];
+		let effort_levels = StatBlock::generate(|_| self.rng.gen_range(0..=10));
+		let moves = self.moveset_for(species);
+
+		Pokemon::new(species)
+			.set_level(self.level_for(species))
+			.set_nature(nature)
+			.set_effort_levels(effort_levels)
+			.add_moves(moves)
+	}
+
+	fn level_for(&self, species: &Species) -> u8
+	{
+		match self.options.level_scaling
+		{
+			LevelScaling::Fixed(level) => level,
+			LevelScaling::BaseStatTotal {
+				min_level,
+				max_level,
+			} =>
+			{
+				let (min_total, max_total) = self.base_stat_total_range;
+				if max_total == min_total
+				{
+					return max_level;
+				}
+
+				let strength = f64::from(species.base_stat_total() - min_total)
+					/ f64::from(max_total - min_total);
+				strength
+					.mul_add(-f64::from(max_level - min_level), f64::from(max_level))
+					.round() as u8
+			}
+		}
+	}
+
+	fn moveset_for(&mut self, species: &Species) -> Vec<&'a Move<'a>>
+	{
+		let mut chosen: Vec<&'a Move<'a>> = Vec::with_capacity(4);
+		while chosen.len() < 4.min(self.moves.len())
+		{
+			let weights = self.moves.iter().map(|mv| {
+				if chosen.contains(mv)
+				{
+					0.0
+				}
+				else if !mv.category.is_damaging()
+				{
+					1.0
+				}
+				else if species.types.contains(mv.move_type)
+				{
+					self.options.stab_weight
+				}
+				else if !chosen
+					.iter()
+					.any(|it| it.category.is_damaging() && it.move_type == mv.move_type)
+				{
+					self.options.coverage_weight
+				}
+				else
+				{
+					1.0
+				}
+			});
+
+			// with the stab or coverage weight at 0 every move left can weigh nothing, in which
+			// case any of them will do
+			let index = match WeightedIndex::new(weights)
+			{
+				Ok(weighted) => weighted.sample(&mut self.rng),
+				Err(_) => *(0..self.moves.len())
+					.filter(|it| !chosen.contains(&self.moves[*it]))
+					.collect::<Vec<_>>()
+					.choose(&mut self.rng)
+					.expect("there should always be a move left to pick"),
+			};
+			chosen.push(self.moves[index]);
+		}
+
+		chosen
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{parsing, MOVE_MAP, NATURE_MAP, SPECIES_MAP};
+
+	fn generate(options: GeneratorOptions) -> Team<'static>
+	{
+		Generator::new(&SPECIES_MAP, &MOVE_MAP, &NATURE_MAP, options).team(6)
+	}
+
+	#[test]
+	fn gives_the_same_team_for_the_same_seed()
+	{
+		let options = GeneratorOptions {
+			seed: Some(42),
+			..GeneratorOptions::default()
+		};
+		let team = parsing::serialize_team(&generate(options), &NATURE_MAP);
+
+		assert_eq!(parsing::serialize_team(&generate(options), &NATURE_MAP), team);
+		let options = GeneratorOptions {
+			seed: Some(43),
+			..options
+		};
+		assert_ne!(parsing::serialize_team(&generate(options), &NATURE_MAP), team);
+	}
+
+	#[test]
+	fn generates_valid_teams()
+	{
+		let scalings = [
+			LevelScaling::Fixed(50),
+			LevelScaling::BaseStatTotal {
+				min_level: 30,
+				max_level: 70,
+			},
+		];
+		for (seed, level_scaling) in (0..10).zip(scalings.into_iter().cycle())
+		{
+			let options = GeneratorOptions {
+				seed: Some(seed),
+				level_scaling,
+				..GeneratorOptions::default()
+			};
+			assert!(options.validate().is_ok());
+
+			let team = generate(options);
+			assert_eq!(team.members.len(), 6);
+			assert!(team.members.iter().all(|it| it.moveset.len() == 4));
+			assert!(team.validate().is_empty(), "{:?}", team.validate());
+		}
+	}
+
+	#[test]
+	fn picks_any_move_when_every_move_left_weighs_nothing()
+	{
+		let options = GeneratorOptions {
+			seed: Some(1),
+			stab_weight: 0.0,
+			coverage_weight: 0.0,
+			..GeneratorOptions::default()
+		};
+		assert!(options.validate().is_ok());
+
+		// only damaging moves, so every one of them is either stab or new coverage at first
+		let damaging = MOVE_MAP
+			.iter()
+			.filter(|it| it.1.category.is_damaging())
+			.map(|(id, mv)| (id.clone(), mv.clone()))
+			.collect::<RegMap<_>>();
+		let team = Generator::new(&SPECIES_MAP, &damaging, &NATURE_MAP, options).team(6);
+		assert!(team.members.iter().all(|it| it.moveset.len() == 4));
+	}
+}
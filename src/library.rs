@@ -0,0 +1,548 @@
+use crate::{data, parsing};
+
+use data::{
+	serialization::{MissingIdError, SerTeam},
+	Move, Nature, RegMap, Species, Team, Type,
+};
+use parsing::PokemonParseError;
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+const TAGS_FILE: &str = "tags.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TeamFormat
+{
+	Paste,
+	Json,
+}
+impl TeamFormat
+{
+	pub fn extension(self) -> &'static str
+	{
+		match self
+		{
+			Self::Paste => "txt",
+			Self::Json => "json",
+		}
+	}
+
+	fn from_extension(extension: &str) -> Option<Self>
+	{
+		match extension
+		{
+			"txt" => Some(Self::Paste),
+			"json" => Some(Self::Json),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct TeamEntry
+{
+	pub name: String,
+	pub format: TeamFormat,
+	pub folders: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SearchQuery
+{
+	Species(Box<str>),
+	Move(Box<str>),
+	Type(Box<str>),
+}
+
+/// The teams a search matched, along with any it couldn't load to check
+#[derive(Debug)]
+pub struct SearchResults
+{
+	pub found: Vec<TeamEntry>,
+	pub skipped: Vec<(TeamEntry, LibraryError)>,
+}
+
+/// A directory of teams, one file per team, with folder tags kept alongside them in `tags.toml`
+pub struct TeamLibrary
+{
+	root: PathBuf,
+}
+impl TeamLibrary
+{
+	pub fn open<P>(root: P) -> Result<Self, LibraryError>
+	where
+		P: Into<PathBuf>,
+	{
+		let root = root.into();
+		fs::create_dir_all(&root)?;
+		Ok(Self { root })
+	}
+
+	pub fn list(&self) -> Result<Vec<TeamEntry>, LibraryError>
+	{
+		let tags = self.read_tags()?;
+		let mut entries = fs::read_dir(&self.root)?
+			.filter_map(|result| {
+				let path = result.ok()?.path();
+				let format = TeamFormat::from_extension(path.extension()?.to_str()?)?;
+				let name = path.file_stem()?.to_str()?.to_owned();
+				Some(TeamEntry {
+					folders: tags.get(&name).cloned().unwrap_or_default(),
+					name,
+					format,
+				})
+			})
+			.collect::<Vec<_>>();
+		entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+		Ok(entries)
+	}
+
+	pub fn load<'a>(
+		&self,
+		name: &str,
+		species_map: &'a RegMap<Species<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+		nature_map: &RegMap<Nature>,
+	) -> Result<Team<'a>, LibraryError>
+	{
+		let (path, format) = self.find(name)?;
+		let data = fs::read_to_string(path)?;
+		let mut team = match format
+		{
+			TeamFormat::Paste =>
+			{
+				parsing::deserialize_team(&data, species_map, move_map, nature_map)?
+			}
+			TeamFormat::Json => serde_json::from_str::<SerTeam>(&data)?
+				.into_team(species_map, move_map)?,
+		};
+		// the file name is the source of truth, in case the file was renamed by hand
+		team.name = String::from(name);
+
+		Ok(team)
+	}
+
+	pub fn create(
+		&self,
+		team: &Team,
+		format: TeamFormat,
+		nature_map: &RegMap<Nature>,
+	) -> Result<(), LibraryError>
+	{
+		validate_name(&team.name)?;
+		if self.find(&team.name).is_ok()
+		{
+			return Err(LibraryError::AlreadyExists(team.name.clone()));
+		}
+
+		let data = match format
+		{
+			TeamFormat::Paste => parsing::serialize_team(team, nature_map),
+			TeamFormat::Json => serde_json::to_string_pretty(&SerTeam::from(team))?,
+		};
+		fs::write(self.path_of(&team.name, format), data)?;
+
+		Ok(())
+	}
+
+	pub fn rename(&self, name: &str, new_name: &str) -> Result<(), LibraryError>
+	{
+		validate_name(new_name)?;
+		let (path, format) = self.find(name)?;
+		if self.find(new_name).is_ok()
+		{
+			return Err(LibraryError::AlreadyExists(String::from(new_name)));
+		}
+		fs::rename(path, self.path_of(new_name, format))?;
+
+		let mut tags = self.read_tags()?;
+		if let Some(folders) = tags.remove(name)
+		{
+			tags.insert(String::from(new_name), folders);
+		}
+		self.write_tags(&tags)
+	}
+
+	pub fn duplicate(&self, name: &str, new_name: &str) -> Result<(), LibraryError>
+	{
+		validate_name(new_name)?;
+		let (path, format) = self.find(name)?;
+		if self.find(new_name).is_ok()
+		{
+			return Err(LibraryError::AlreadyExists(String::from(new_name)));
+		}
+		fs::copy(path, self.path_of(new_name, format))?;
+
+		let mut tags = self.read_tags()?;
+		if let Some(folders) = tags.get(name).cloned()
+		{
+			tags.insert(String::from(new_name), folders);
+		}
+		self.write_tags(&tags)
+	}
+
+	pub fn delete(&self, name: &str) -> Result<(), LibraryError>
+	{
+		let (path, _) = self.find(name)?;
+		fs::remove_file(path)?;
+
+		let mut tags = self.read_tags()?;
+		tags.remove(name);
+		self.write_tags(&tags)
+	}
+
+	pub fn tag(&self, name: &str, folder: &str) -> Result<(), LibraryError>
+	{
+		self.find(name)?;
+		let mut tags = self.read_tags()?;
+		let folders = tags.entry(String::from(name)).or_default();
+		if !folders.iter().any(|it| it == folder)
+		{
+			folders.push(String::from(folder));
+			folders.sort();
+		}
+		self.write_tags(&tags)
+	}
+
+	pub fn untag(&self, name: &str, folder: &str) -> Result<(), LibraryError>
+	{
+		self.find(name)?;
+		let mut tags = self.read_tags()?;
+		if let Some(folders) = tags.get_mut(name)
+		{
+			folders.retain(|it| it != folder);
+			if folders.is_empty()
+			{
+				tags.remove(name);
+			}
+		}
+		self.write_tags(&tags)
+	}
+
+	pub fn in_folder(&self, folder: &str) -> Result<Vec<TeamEntry>, LibraryError>
+	{
+		Ok(self
+			.list()?
+			.into_iter()
+			.filter(|it| it.folders.iter().any(|f| f == folder))
+			.collect())
+	}
+
+	pub fn search(
+		&self,
+		query: &SearchQuery,
+		species_map: &RegMap<Species>,
+		move_map: &RegMap<Move>,
+		nature_map: &RegMap<Nature>,
+		type_map: &RegMap<Type>,
+	) -> Result<SearchResults, LibraryError>
+	{
+		let mut found = Vec::new();
+		let mut skipped = Vec::new();
+		for entry in self.list()?
+		{
+			// one broken file shouldn't hide every other team from the search
+			let team = match self.load(&entry.name, species_map, move_map, nature_map)
+			{
+				Ok(team) => team,
+				Err(err) =>
+				{
+					skipped.push((entry, err));
+					continue;
+				}
+			};
+			let is_match = match query
+			{
+				SearchQuery::Species(id) => team.has_species(id),
+				SearchQuery::Move(id) => team.has_move(id),
+				SearchQuery::Type(id) => type_map.get(id).is_some_and(|typ| team.has_type(typ)),
+			};
+			if is_match
+			{
+				found.push(entry);
+			}
+		}
+
+		Ok(SearchResults { found, skipped })
+	}
+
+	fn find(&self, name: &str) -> Result<(PathBuf, TeamFormat), LibraryError>
+	{
+		[TeamFormat::Paste, TeamFormat::Json]
+			.into_iter()
+			.map(|format| (self.path_of(name, format), format))
+			.find(|it| it.0.is_file())
+			.ok_or_else(|| LibraryError::NotFound(String::from(name)))
+	}
+
+	fn path_of(&self, name: &str, format: TeamFormat) -> PathBuf
+	{
+		self.root.join(format!("{name}.{}", format.extension()))
+	}
+
+	fn read_tags(&self) -> Result<BTreeMap<String, Vec<String>>, LibraryError>
+	{
+		let path = self.root.join(TAGS_FILE);
+		if path.is_file()
+		{
+			Ok(toml::from_str(&fs::read_to_string(path)?)?)
+		}
+		else
+		{
+			Ok(BTreeMap::new())
+		}
+	}
+
+	fn write_tags(&self, tags: &BTreeMap<String, Vec<String>>) -> Result<(), LibraryError>
+	{
+		fs::write(self.root.join(TAGS_FILE), toml::to_string(tags)?)?;
+		Ok(())
+	}
+}
+
+fn validate_name(name: &str) -> Result<(), LibraryError>
+{
+	if name.trim().is_empty()
+		|| name.starts_with('.')
+		|| name.contains(['/', '\\'])
+		|| Path::new(name).components().count() != 1
+	{
+		Err(LibraryError::InvalidName(String::from(name)))
+	}
+	else
+	{
+		Ok(())
+	}
+}
+
+#[derive(Debug)]
+pub enum LibraryError
+{
+	Io(std::io::Error),
+	Parse(PokemonParseError),
+	Json(serde_json::Error),
+	Tags(String),
+	MissingId(Box<str>),
+	NotFound(String),
+	AlreadyExists(String),
+	InvalidName(String),
+}
+impl std::fmt::Display for LibraryError
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			Self::Io(err) => write!(f, "{err}"),
+			Self::Parse(err) => write!(f, "{err}"),
+			Self::Json(err) => write!(f, "{err}"),
+			Self::Tags(err) => write!(f, "could not read or write {TAGS_FILE}: {err}"),
+			Self::MissingId(id) => write!(f, "could not find '{id}'"),
+			Self::NotFound(name) => write!(f, "no team named '{name}'"),
+			Self::AlreadyExists(name) => write!(f, "a team named '{name}' already exists"),
+			Self::InvalidName(name) => write!(f, "'{name}' is not a valid team name"),
+		}
+	}
+}
+impl From<std::io::Error> for LibraryError
+{
+	fn from(value: std::io::Error) -> Self
+	{
+		Self::Io(value)
+	}
+}
+impl From<PokemonParseError> for LibraryError
+{
+	fn from(value: PokemonParseError) -> Self
+	{
+		Self::Parse(value)
+	}
+}
+impl From<serde_json::Error> for LibraryError
+{
+	fn from(value: serde_json::Error) -> Self
+	{
+		Self::Json(value)
+	}
+}
+impl From<toml::de::Error> for LibraryError
+{
+	fn from(value: toml::de::Error) -> Self
+	{
+		Self::Tags(value.to_string())
+	}
+}
+impl From<toml::ser::Error> for LibraryError
+{
+	fn from(value: toml::ser::Error) -> Self
+	{
+		Self::Tags(value.to_string())
+	}
+}
+impl From<MissingIdError> for LibraryError
+{
+	fn from(value: MissingIdError) -> Self
+	{
+		Self::MissingId(value.0)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{MOVE_MAP, NATURE_MAP, SPECIES_MAP, TYPE_MAP};
+
+	const PASTE: &str = "=== Sand ===
+
+Garchomp
+Jolly Nature
+- Dragon Claw
+- Stone Edge";
+
+	/// An empty library in its own directory, removed again when the test finishes
+	struct TempLibrary(TeamLibrary);
+	impl TempLibrary
+	{
+		fn new(name: &str) -> Self
+		{
+			let root = std::env::temp_dir().join(format!(
+				"pla_showdown_library_{name}_{}",
+				std::process::id()
+			));
+			let _ = fs::remove_dir_all(&root);
+			Self(TeamLibrary::open(root).unwrap())
+		}
+	}
+	impl Drop for TempLibrary
+	{
+		fn drop(&mut self)
+		{
+			let _ = fs::remove_dir_all(&self.0.root);
+		}
+	}
+
+	fn team() -> Team<'static>
+	{
+		parsing::deserialize_team(PASTE, &SPECIES_MAP, &MOVE_MAP, &NATURE_MAP).unwrap()
+	}
+
+	fn search(library: &TeamLibrary, query: &SearchQuery) -> Vec<String>
+	{
+		library
+			.search(query, &SPECIES_MAP, &MOVE_MAP, &NATURE_MAP, &TYPE_MAP)
+			.unwrap()
+			.found
+			.into_iter()
+			.map(|it| it.name)
+			.collect()
+	}
+
+	#[test]
+	fn creates_and_loads_teams()
+	{
+		let library = TempLibrary::new("create");
+		let library = &library.0;
+		library.create(&team(), TeamFormat::Paste, &NATURE_MAP).unwrap();
+
+		let entries = library.list().unwrap();
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].name, "Sand");
+		assert_eq!(entries[0].format, TeamFormat::Paste);
+		let loaded = library
+			.load("Sand", &SPECIES_MAP, &MOVE_MAP, &NATURE_MAP)
+			.unwrap();
+		assert!(loaded.has_species("garchomp"));
+		assert!(matches!(
+			library.create(&team(), TeamFormat::Json, &NATURE_MAP),
+			Err(LibraryError::AlreadyExists(_))
+		));
+	}
+
+	#[test]
+	fn renames_teams_along_with_their_tags()
+	{
+		let library = TempLibrary::new("rename");
+		let library = &library.0;
+		library.create(&team(), TeamFormat::Json, &NATURE_MAP).unwrap();
+		library.tag("Sand", "ladder").unwrap();
+		library.rename("Sand", "Dunes").unwrap();
+
+		let entries = library.list().unwrap();
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].name, "Dunes");
+		assert_eq!(entries[0].format, TeamFormat::Json);
+		assert_eq!(entries[0].folders, ["ladder"]);
+		assert!(matches!(
+			library.rename("Sand", "Other"),
+			Err(LibraryError::NotFound(_))
+		));
+		assert!(matches!(
+			library.rename("Dunes", "../Dunes"),
+			Err(LibraryError::InvalidName(_))
+		));
+	}
+
+	#[test]
+	fn tags_teams_into_folders()
+	{
+		let library = TempLibrary::new("tag");
+		let library = &library.0;
+		library.create(&team(), TeamFormat::Paste, &NATURE_MAP).unwrap();
+		library.tag("Sand", "ladder").unwrap();
+		library.tag("Sand", "ladder").unwrap();
+		library.tag("Sand", "favourites").unwrap();
+
+		assert_eq!(library.list().unwrap()[0].folders, ["favourites", "ladder"]);
+		assert_eq!(library.in_folder("ladder").unwrap().len(), 1);
+
+		library.untag("Sand", "ladder").unwrap();
+		assert!(library.in_folder("ladder").unwrap().is_empty());
+		assert!(matches!(
+			library.tag("Missing", "ladder"),
+			Err(LibraryError::NotFound(_))
+		));
+	}
+
+	#[test]
+	fn searches_by_species_move_and_type()
+	{
+		let library = TempLibrary::new("search");
+		let library = &library.0;
+		library.create(&team(), TeamFormat::Paste, &NATURE_MAP).unwrap();
+
+		let species = SearchQuery::Species("garchomp".into());
+		assert_eq!(search(library, &species), ["Sand"]);
+		assert_eq!(search(library, &SearchQuery::Move("stone_edge".into())), ["Sand"]);
+		assert_eq!(search(library, &SearchQuery::Type("dragon".into())), ["Sand"]);
+		assert!(search(library, &SearchQuery::Species("pikachu".into())).is_empty());
+	}
+
+	#[test]
+	fn search_skips_and_reports_files_that_do_not_load()
+	{
+		let library = TempLibrary::new("search_broken");
+		let library = &library.0;
+		library.create(&team(), TeamFormat::Paste, &NATURE_MAP).unwrap();
+		fs::write(library.path_of("Broken", TeamFormat::Paste), "Missingno\n- Splash").unwrap();
+		fs::write(library.path_of("Garbled", TeamFormat::Json), "{").unwrap();
+
+		let species = SearchQuery::Species("garchomp".into());
+		let results = library
+			.search(&species, &SPECIES_MAP, &MOVE_MAP, &NATURE_MAP, &TYPE_MAP)
+			.unwrap();
+		let found = results.found.iter().map(|it| &*it.name).collect::<Vec<_>>();
+		assert_eq!(found, ["Sand"]);
+		let mut skipped = results.skipped.iter().collect::<Vec<_>>();
+		skipped.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+		assert!(matches!(
+			skipped[..],
+			[(broken, LibraryError::Parse(_)), (garbled, LibraryError::Json(_))]
+				if broken.name == "Broken" && garbled.name == "Garbled"
+		));
+	}
+}
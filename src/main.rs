@@ -1,24 +1,35 @@
 #![feature(let_chains, result_option_inspect)]
 #![allow(dead_code)]
 
+mod analysis;
+mod battle;
+mod cli;
 mod data;
+mod generator;
+mod library;
+mod pa8;
 mod parsing;
+mod teambuilder;
 
 use crate::data::{
 	serialization::{SerMove, SerSpecies, SerStatus},
-	Move, Nature, RegMap, Species, StatusCondition, Type,
+	Move, Nature, RegMap, Ruleset, Species, StatusCondition, Type, TypeChart,
 };
 use lazy_static::lazy_static;
 
 lazy_static! {
-	static ref TYPE_MAP: RegMap<Type> = register("assets/types/");
+	static ref TYPE_MAP: RegMap<Type> = data::index_types(register("assets/types/"));
+	static ref TYPE_CHART: TypeChart = TypeChart::new(
+		&TYPE_MAP,
+		toml::from_str(&std::fs::read_to_string("assets/type_chart.toml").unwrap()).unwrap()
+	);
 	static ref MOVE_MAP: RegMap<Move<'static>> = register::<SerMove>("assets/moves/")
 		.iter()
 		.map(|it| (it.0.to_owned(), it.1.clone().into_move(&TYPE_MAP)))
 		.collect();
 	static ref SPECIES_MAP: RegMap<Species<'static>> = register::<SerSpecies>("assets/species/")
 		.iter()
-		.map(|it| (it.0.to_owned(), it.1.clone().into_species(&TYPE_MAP)))
+		.map(|it| (it.0.to_owned(), it.1.clone().into_species(&TYPE_MAP, &TYPE_CHART)))
 		.collect();
 	static ref STATUS_MAP: RegMap<StatusCondition<'static>> =
 		register::<SerStatus>("assets/statuses/")
@@ -27,15 +38,56 @@ lazy_static! {
 			.collect();
 	static ref NATURE_MAP: RegMap<Nature> =
 		toml::from_str(&std::fs::read_to_string("assets/natures.toml").unwrap()).unwrap();
+	static ref GAME_IDS: pa8::GameIds =
+		toml::from_str(&std::fs::read_to_string("assets/game_ids.toml").unwrap()).unwrap();
+	static ref RULESET: Ruleset = {
+		let rules: Ruleset =
+			toml::from_str(&std::fs::read_to_string("assets/ruleset.toml").unwrap()).unwrap();
+		rules.validate().unwrap_or_else(|err| panic!("assets/ruleset.toml: {err}"));
+		rules
+	};
+	static ref GRIT_TABLE: analysis::GritTable =
+		toml::from_str(&std::fs::read_to_string("assets/grit.toml").unwrap()).unwrap();
 }
 
 fn main()
 {
-	println!("Types: {}", TYPE_MAP.len());
-	println!("Species: {}", SPECIES_MAP.len());
-	println!("Moves: {}", MOVE_MAP.len());
-	println!("Statuses: {}", STATUS_MAP.len());
-	println!("Natures: {}", NATURE_MAP.len());
+	let args = std::env::args().skip(1).collect::<Vec<_>>();
+	let result = match args.first().map(String::as_str)
+	{
+		Some("library") => cli::library_command(&args[1..]),
+		Some("import-pa8") => cli::import_pa8_command(&args[1..]),
+		Some("random") => cli::random_command(&args[1..]),
+		Some("build") => cli::build_command(&args[1..]),
+		Some("infer") => cli::infer_command(&args[1..]),
+		Some("grit") => cli::grit_command(&args[1..]),
+		Some("speed") => cli::speed_command(&args[1..]),
+		Some("coverage") => cli::coverage_command(&args[1..]),
+		Some("matchup") => cli::matchup_command(&args[1..]),
+		Some("ko") => cli::ko_command(&args[1..]),
+		Some("style") => cli::style_command(&args[1..]),
+		Some("validate") => cli::validate_command(&args[1..]),
+		Some("battle") => cli::battle_command(&args[1..]),
+		Some("hint") => cli::hint_command(&args[1..]),
+		Some("tournament") => cli::tournament_command(&args[1..]),
+		Some("simulate") => cli::simulate_command(&args[1..]),
+		Some(command) => Err(format!("unknown command '{command}'")),
+		None =>
+		{
+			println!("Types: {}", TYPE_MAP.len());
+			println!("Species: {}", SPECIES_MAP.len());
+			println!("Moves: {}", MOVE_MAP.len());
+			println!("Statuses: {}", STATUS_MAP.len());
+			println!("Natures: {}", NATURE_MAP.len());
+			Ok(())
+		}
+	};
+
+	if let Err(err) = result
+	{
+		eprintln!("{err}");
+		std::process::exit(1);
+	}
 }
 
 fn register<T>(dir_path: &str) -> RegMap<T>
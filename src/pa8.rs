@@ -0,0 +1,558 @@
+use crate::data;
+
+use data::{Move, Nature, Pokemon, RegMap, Species, StatBlock};
+use std::collections::HashMap;
+
+pub const STORED_SIZE: usize = 0x168;
+pub const PARTY_SIZE: usize = 0x178;
+
+const BLOCK_START: usize = 0x08;
+const BLOCK_SIZE: usize = 0x58;
+
+// offsets into the decrypted and unshuffled data, following PKHeX's PA8 layout
+const CHECKSUM: usize = 0x06;
+const SPECIES: usize = 0x08;
+const TRAINER_ID: usize = 0x0C;
+const SECRET_ID: usize = 0x0E;
+const EXP: usize = 0x10;
+const FLAGS: usize = 0x16;
+const PID: usize = 0x1C;
+const STAT_NATURE: usize = 0x21;
+const FORM: usize = 0x24;
+const ALPHA_MOVE: usize = 0x3E;
+const MOVES: usize = 0x54;
+const MOVE_PP: usize = 0x5C;
+const NICKNAME: usize = 0x60;
+const NICKNAME_LENGTH: usize = 13;
+const IV32: usize = 0x94;
+const EFFORT_LEVELS: usize = 0xA4;
+const PURCHASED_RECORD: usize = 0x13F;
+const MASTERED_RECORD: usize = 0x147;
+const PARTY_LEVEL: usize = 0x168;
+
+const ALPHA_FLAG: u8 = 0x20;
+
+// which encrypted block ends up in each position, indexed by the shuffle value
+const BLOCK_POSITIONS: [[usize; 4]; 24] = [
+	[0, 1, 2, 3],
+	[0, 1, 3, 2],
+	[0, 2, 1, 3],
+	[0, 3, 1, 2],
+	[0, 2, 3, 1],
+	[0, 3, 2, 1],
+	[1, 0, 2, 3],
+	[1, 0, 3, 2],
+	[2, 0, 1, 3],
+	[3, 0, 1, 2],
+	[2, 0, 3, 1],
+	[3, 0, 2, 1],
+	[1, 2, 0, 3],
+	[1, 3, 0, 2],
+	[2, 1, 0, 3],
+	[3, 1, 0, 2],
+	[2, 3, 0, 1],
+	[3, 2, 0, 1],
+	[1, 2, 3, 0],
+	[1, 3, 2, 0],
+	[2, 1, 3, 0],
+	[3, 1, 2, 0],
+	[2, 3, 1, 0],
+	[3, 2, 1, 0],
+];
+
+// natures in the order the games number them
+const NATURES: [&str; 25] = [
+	"hardy", "lonely", "brave", "adamant", "naughty", "bold", "docile", "relaxed", "impish", "lax",
+	"timid", "hasty", "serious", "jolly", "naive", "modest", "mild", "quiet", "bashful", "rash",
+	"calm", "gentle", "sassy", "careful", "quirky",
+];
+
+/// The national dex and move numbers the games use, mapped from our ids
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct GameIds
+{
+	pub species: HashMap<Box<str>, GameSpecies>,
+	pub moves: HashMap<Box<str>, u16>,
+}
+impl GameIds
+{
+	pub fn species_id(&self, species: u16, form: u16) -> Option<&str>
+	{
+		self.game_species(species, form).map(|it| it.0)
+	}
+
+	pub fn game_species(&self, species: u16, form: u16) -> Option<(&str, &GameSpecies)>
+	{
+		let find = |form| {
+			self.species
+				.iter()
+				.find(|it| it.1.species == species && it.1.form == form)
+				.map(|it| (&**it.0, it.1))
+		};
+		// forms that we don't tell apart (ie. east/west sea shellos) fall back to the base form
+		find(form).or_else(|| find(0))
+	}
+
+	pub fn move_id(&self, move_number: u16) -> Option<&str>
+	{
+		self.moves
+			.iter()
+			.find(|it| *it.1 == move_number)
+			.map(|it| &**it.0)
+	}
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct GameSpecies
+{
+	pub species: u16,
+	#[serde(default)]
+	pub form: u16,
+	pub growth_rate: GrowthRate,
+}
+
+/// How much exp a species needs to reach each level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrowthRate
+{
+	Erratic,
+	Fast,
+	MediumFast,
+	MediumSlow,
+	Slow,
+	Fluctuating,
+}
+impl GrowthRate
+{
+	/// The total exp needed to reach `level`
+	pub fn exp_for(self, level: u8) -> u32
+	{
+		if level <= 1
+		{
+			return 0;
+		}
+
+		let n = i64::from(level);
+		let cube = n * n * n;
+		let exp = match self
+		{
+			Self::Erratic => match n
+			{
+				..=49 => cube * (100 - n) / 50,
+				50..=67 => cube * (150 - n) / 100,
+				68..=97 => cube * ((1911 - (10 * n)) / 3) / 500,
+				_ => cube * (160 - n) / 100,
+			},
+			Self::Fast => cube * 4 / 5,
+			Self::MediumFast => cube,
+			Self::MediumSlow => (cube * 6 / 5) - (15 * n * n) + (100 * n) - 140,
+			Self::Slow => cube * 5 / 4,
+			Self::Fluctuating => match n
+			{
+				..=14 => cube * (((n + 1) / 3) + 24) / 50,
+				15..=35 => cube * (n + 14) / 50,
+				_ => cube * ((n / 2) + 32) / 50,
+			},
+		};
+		u32::try_from(exp).unwrap_or(0)
+	}
+
+	/// The level a pokemon with `exp` total exp is at, up to 100
+	pub fn level_at(self, exp: u32) -> u8
+	{
+		(1..=100)
+			.take_while(|it| self.exp_for(*it) <= exp)
+			.last()
+			.unwrap_or(1)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct Pa8
+{
+	pub encryption_constant: u32,
+	pub species: u16,
+	pub form: u16,
+	pub nickname: Option<String>,
+	/// Only party data stores the level, so this is `None` for boxed Pokémon. Their level comes
+	/// from `exp` instead
+	pub level: Option<u8>,
+	pub exp: u32,
+	pub nature: u8,
+	pub is_shiny: bool,
+	pub is_alpha: bool,
+	pub effort_levels: StatBlock,
+	pub moves: [MoveSlot; 4],
+	pub alpha_move: u16,
+	/// Bit flags over the species' learnset of which moves have been bought from the move tutor
+	pub purchased_flags: u64,
+	/// Bit flags over the species' learnset of which moves have been mastered
+	pub mastered_flags: u64,
+}
+impl Pa8
+{
+	pub fn decode(data: &[u8]) -> Result<Self, Pa8Error>
+	{
+		let data = decrypt(data)?;
+
+		let u16_at = |offset| u16::from_le_bytes([data[offset], data[offset + 1]]);
+		let u32_at = |offset| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+		let u64_at = |offset| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+		let pid = u32_at(PID);
+		let shiny_xor = u32::from(u16_at(TRAINER_ID))
+			^ u32::from(u16_at(SECRET_ID))
+			^ (pid >> 16)
+			^ (pid & 0xFFFF);
+		let is_nicknamed = u32_at(IV32) >> 31 == 1;
+
+		Ok(Self {
+			encryption_constant: u32_at(0),
+			species: u16_at(SPECIES),
+			form: u16_at(FORM),
+			nickname: is_nicknamed.then(|| {
+				String::from_utf16_lossy(
+					&(0..NICKNAME_LENGTH)
+						.map(|i| u16_at(NICKNAME + (i * 2)))
+						.take_while(|it| *it != 0)
+						.collect::<Vec<_>>(),
+				)
+			}),
+			level: (data.len() == PARTY_SIZE).then(|| data[PARTY_LEVEL]),
+			exp: u32_at(EXP),
+			nature: data[STAT_NATURE],
+			is_shiny: shiny_xor < 16,
+			is_alpha: data[FLAGS] & ALPHA_FLAG != 0,
+			// the games store stats in hp/atk/def/spe/spa/spd order
+			effort_levels: StatBlock {
+				hp: i32::from(data[EFFORT_LEVELS]),
+				atk: i32::from(data[EFFORT_LEVELS + 1]),
+				def: i32::from(data[EFFORT_LEVELS + 2]),
+				spe: i32::from(data[EFFORT_LEVELS + 3]),
+				spatk: i32::from(data[EFFORT_LEVELS + 4]),
+				spdef: i32::from(data[EFFORT_LEVELS + 5]),
+			},
+			moves: std::array::from_fn(|i| MoveSlot {
+				move_number: u16_at(MOVES + (i * 2)),
+				pp: data[MOVE_PP + i],
+			}),
+			alpha_move: u16_at(ALPHA_MOVE),
+			purchased_flags: u64_at(PURCHASED_RECORD),
+			mastered_flags: u64_at(MASTERED_RECORD),
+		})
+	}
+
+	pub fn is_mastered(&self, learnset_index: u32) -> bool
+	{
+		learnset_index < 64 && self.mastered_flags & (1 << learnset_index) != 0
+	}
+
+	pub fn is_purchased(&self, learnset_index: u32) -> bool
+	{
+		learnset_index < 64 && self.purchased_flags & (1 << learnset_index) != 0
+	}
+
+	pub fn to_pokemon<'a>(
+		&self,
+		game_ids: &GameIds,
+		species_map: &'a RegMap<Species<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+		nature_map: &RegMap<Nature>,
+	) -> Result<Pokemon<'a>, Pa8Error>
+	{
+		let (species, game_species) = game_ids
+			.game_species(self.species, self.form)
+			.and_then(|(id, game_species)| Some((species_map.get(id)?, game_species)))
+			.ok_or(Pa8Error::UnknownSpecies(self.species, self.form))?;
+		let nature = NATURES
+			.get(usize::from(self.nature))
+			.and_then(|name| nature_map.get(*name))
+			.ok_or(Pa8Error::UnknownNature(self.nature))?;
+		let moves = self
+			.moves
+			.iter()
+			.filter(|it| it.move_number != 0)
+			.map(|it| {
+				game_ids
+					.move_id(it.move_number)
+					.and_then(|id| move_map.get(id))
+					.ok_or(Pa8Error::UnknownMove(it.move_number))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let alpha_move = (self.alpha_move != 0)
+			.then(|| {
+				game_ids
+					.move_id(self.alpha_move)
+					.and_then(|id| move_map.get(id))
+					.ok_or(Pa8Error::UnknownMove(self.alpha_move))
+			})
+			.transpose()?;
+		if self.effort_levels.iter().any(|it| !(0..=10).contains(&it.1))
+		{
+			return Err(Pa8Error::InvalidEffortLevels(self.effort_levels.clone()));
+		}
+
+		Ok(Pokemon::new(species)
+			.set_nickname(self.nickname.clone())
+			.set_level(
+				self.level
+					.unwrap_or_else(|| game_species.growth_rate.level_at(self.exp)),
+			)
+			.set_shiny(self.is_shiny)
+			.set_alpha(self.is_alpha)
+			.set_nature(*nature)
+			.set_effort_levels(self.effort_levels.clone())
+			.add_moves(moves)
+			.set_alpha_move(alpha_move))
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MoveSlot
+{
+	pub move_number: u16,
+	pub pp: u8,
+}
+
+/// Decrypts and unshuffles an encrypted stored or party PA8, checking its checksum
+pub fn decrypt(data: &[u8]) -> Result<Vec<u8>, Pa8Error>
+{
+	if data.len() != STORED_SIZE && data.len() != PARTY_SIZE
+	{
+		return Err(Pa8Error::WrongSize(data.len()));
+	}
+
+	let mut data = data.to_vec();
+	let encryption_constant = u32::from_le_bytes(data[0..4].try_into().unwrap());
+	crypt(&mut data, encryption_constant);
+
+	let positions = BLOCK_POSITIONS[shuffle_value(encryption_constant)];
+	let shuffled = data[BLOCK_START..STORED_SIZE].to_vec();
+	for (block, position) in positions.into_iter().enumerate()
+	{
+		data[(BLOCK_START + (block * BLOCK_SIZE))..(BLOCK_START + ((block + 1) * BLOCK_SIZE))]
+			.copy_from_slice(&shuffled[(position * BLOCK_SIZE)..((position + 1) * BLOCK_SIZE)]);
+	}
+
+	if checksum(&data) == u16::from_le_bytes([data[CHECKSUM], data[CHECKSUM + 1]])
+	{
+		Ok(data)
+	}
+	else
+	{
+		Err(Pa8Error::BadChecksum)
+	}
+}
+
+/// The inverse of [`decrypt`], recalculating the checksum before shuffling
+pub fn encrypt(data: &[u8]) -> Result<Vec<u8>, Pa8Error>
+{
+	if data.len() != STORED_SIZE && data.len() != PARTY_SIZE
+	{
+		return Err(Pa8Error::WrongSize(data.len()));
+	}
+
+	let mut data = data.to_vec();
+	let checksum = checksum(&data).to_le_bytes();
+	data[CHECKSUM..CHECKSUM + 2].copy_from_slice(&checksum);
+
+	let encryption_constant = u32::from_le_bytes(data[0..4].try_into().unwrap());
+	let positions = BLOCK_POSITIONS[shuffle_value(encryption_constant)];
+	let unshuffled = data[BLOCK_START..STORED_SIZE].to_vec();
+	for (block, position) in positions.into_iter().enumerate()
+	{
+		data[(BLOCK_START + (position * BLOCK_SIZE))..(BLOCK_START + ((position + 1) * BLOCK_SIZE))]
+			.copy_from_slice(&unshuffled[(block * BLOCK_SIZE)..((block + 1) * BLOCK_SIZE)]);
+	}
+	crypt(&mut data, encryption_constant);
+
+	Ok(data)
+}
+
+fn shuffle_value(encryption_constant: u32) -> usize
+{
+	// the table only has 24 orders; values 24-31 wrap around onto the first few
+	((encryption_constant >> 13) & 31) as usize % BLOCK_POSITIONS.len()
+}
+
+fn crypt(data: &mut [u8], encryption_constant: u32)
+{
+	// the party stats are encrypted separately, with the seed starting over
+	crypt_range(&mut data[BLOCK_START..STORED_SIZE], encryption_constant);
+	if data.len() == PARTY_SIZE
+	{
+		crypt_range(&mut data[STORED_SIZE..PARTY_SIZE], encryption_constant);
+	}
+}
+
+fn crypt_range(data: &mut [u8], mut seed: u32)
+{
+	for word in data.chunks_exact_mut(2)
+	{
+		seed = seed.wrapping_mul(0x41C6_4E6D).wrapping_add(0x6073);
+		let key = ((seed >> 16) as u16).to_le_bytes();
+		word[0] ^= key[0];
+		word[1] ^= key[1];
+	}
+}
+
+fn checksum(data: &[u8]) -> u16
+{
+	data[BLOCK_START..STORED_SIZE]
+		.chunks_exact(2)
+		.fold(0u16, |sum, word| {
+			sum.wrapping_add(u16::from_le_bytes([word[0], word[1]]))
+		})
+}
+
+#[derive(Debug)]
+pub enum Pa8Error
+{
+	WrongSize(usize),
+	BadChecksum,
+	UnknownSpecies(u16, u16),
+	UnknownMove(u16),
+	UnknownNature(u8),
+	InvalidEffortLevels(StatBlock),
+}
+impl std::fmt::Display for Pa8Error
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			Self::WrongSize(size) => write!(
+				f,
+				"expected {STORED_SIZE} or {PARTY_SIZE} bytes of PA8 data, found {size}"
+			),
+			Self::BadChecksum => write!(f, "checksum does not match, the file may be corrupt"),
+			Self::UnknownSpecies(species, form) =>
+			{
+				write!(f, "unknown species #{species} (form {form})")
+			}
+			Self::UnknownMove(mv) => write!(f, "unknown move #{mv}"),
+			Self::UnknownNature(nature) => write!(f, "unknown nature #{nature}"),
+			Self::InvalidEffortLevels(els) => write!(f, "effort levels out of range: {els:?}"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{GAME_IDS, MOVE_MAP, NATURE_MAP, SPECIES_MAP};
+
+	const GARCHOMP_PARTY: &[u8] = include_bytes!("../tests/fixtures/garchomp_party.pa8");
+	const ARCANINE_STORED: &[u8] = include_bytes!("../tests/fixtures/arcanine_hisui_stored.pa8");
+
+	#[test]
+	fn decodes_party_data()
+	{
+		let pa8 = Pa8::decode(GARCHOMP_PARTY).unwrap();
+
+		assert_eq!(pa8.species, 445);
+		assert_eq!(pa8.form, 0);
+		assert_eq!(pa8.nickname.as_deref(), Some("Spiky"));
+		assert_eq!(pa8.level, Some(50));
+		assert_eq!(pa8.nature, 13);
+		assert!(pa8.is_shiny);
+		assert!(pa8.is_alpha);
+		assert_eq!(pa8.effort_levels.hp, 10);
+		assert_eq!(pa8.effort_levels.spe, 10);
+		assert_eq!(pa8.effort_levels.spatk, 0);
+		assert_eq!(pa8.moves.map(|it| it.move_number), [337, 444, 414, 0]);
+		assert_eq!(pa8.moves[0].pp, 15);
+		assert_eq!(pa8.alpha_move, 444);
+		assert!(pa8.is_mastered(0) && pa8.is_mastered(1) && !pa8.is_mastered(2));
+		assert!(pa8.is_purchased(2) && !pa8.is_purchased(0));
+	}
+
+	#[test]
+	fn decodes_stored_data()
+	{
+		let pa8 = Pa8::decode(ARCANINE_STORED).unwrap();
+
+		assert_eq!((pa8.species, pa8.form), (59, 1));
+		assert_eq!(pa8.nickname, None);
+		assert_eq!(pa8.level, None);
+		assert_eq!(pa8.exp, 93_610);
+		assert!(!pa8.is_shiny);
+		assert!(!pa8.is_alpha);
+	}
+
+	#[test]
+	fn maps_into_pokemon()
+	{
+		let pokemon = Pa8::decode(GARCHOMP_PARTY)
+			.unwrap()
+			.to_pokemon(&GAME_IDS, &SPECIES_MAP, &MOVE_MAP, &NATURE_MAP)
+			.unwrap();
+
+		assert_eq!(&*pokemon.species.id, "garchomp");
+		assert_eq!(pokemon.name(), "Spiky");
+		assert_eq!(pokemon.level, 50);
+		assert_eq!(pokemon.nature, NATURE_MAP["jolly"]);
+		assert_eq!(pokemon.moveset.len(), 3);
+		assert!(pokemon.moveset.contains(&MOVE_MAP["stone_edge"]));
+		assert!(pokemon.is_alpha);
+		assert_eq!(pokemon.alpha_move, Some(&MOVE_MAP["stone_edge"]));
+
+		let pokemon = Pa8::decode(ARCANINE_STORED)
+			.unwrap()
+			.to_pokemon(&GAME_IDS, &SPECIES_MAP, &MOVE_MAP, &NATURE_MAP)
+			.unwrap();
+
+		assert_eq!(&*pokemon.species.id, "arcanine-hisui");
+		// boxed pokemon don't store their level, so it comes from exp on arcanine's slow curve
+		assert_eq!(pokemon.level, 42);
+	}
+
+	#[test]
+	fn finds_levels_from_exp()
+	{
+		assert_eq!(GrowthRate::MediumFast.exp_for(100), 1_000_000);
+		assert_eq!(GrowthRate::Erratic.exp_for(100), 600_000);
+		assert_eq!(GrowthRate::Fluctuating.exp_for(100), 1_640_000);
+		assert_eq!(GrowthRate::MediumSlow.exp_for(2), 9);
+
+		for rate in [
+			GrowthRate::Erratic,
+			GrowthRate::Fast,
+			GrowthRate::MediumFast,
+			GrowthRate::MediumSlow,
+			GrowthRate::Slow,
+			GrowthRate::Fluctuating,
+		]
+		{
+			assert_eq!(rate.level_at(0), 1);
+			assert_eq!(rate.level_at(u32::MAX), 100);
+			for level in 2..=100
+			{
+				assert_eq!(rate.level_at(rate.exp_for(level)), level);
+				assert_eq!(rate.level_at(rate.exp_for(level) - 1), level - 1);
+			}
+		}
+	}
+
+	#[test]
+	fn round_trips_through_encrypt()
+	{
+		let decrypted = decrypt(GARCHOMP_PARTY).unwrap();
+		assert_eq!(encrypt(&decrypted).unwrap(), GARCHOMP_PARTY);
+	}
+
+	#[test]
+	fn rejects_bad_data()
+	{
+		assert!(matches!(
+			Pa8::decode(&GARCHOMP_PARTY[..100]),
+			Err(Pa8Error::WrongSize(100))
+		));
+
+		let mut corrupt = GARCHOMP_PARTY.to_vec();
+		corrupt[0x40] ^= 0xFF;
+		assert!(matches!(Pa8::decode(&corrupt), Err(Pa8Error::BadChecksum)));
+	}
+}
@@ -1,6 +1,6 @@
 use crate::data;
 
-use data::{Move, Nature, Pokemon, RegMap, Species, Stat, StatBlock};
+use data::{Move, Nature, Pokemon, RegMap, Species, Stat, StatBlock, Team};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::HashMap;
@@ -16,24 +16,57 @@ pub fn deserialize_pokemon<'a>(
 	move_map: &'a RegMap<Move<'a>>,
 	nature_map: &RegMap<Nature>,
 ) -> Result<Pokemon<'a>, PokemonParseError>
+{
+	deserialize_pokemon_with(
+		data,
+		species_map,
+		move_map,
+		nature_map,
+		ParseOptions::default(),
+	)
+	.map(|it| it.0)
+}
+
+pub fn deserialize_pokemon_with<'a>(
+	data: &str,
+	species_map: &'a RegMap<Species<'a>>,
+	move_map: &'a RegMap<Move<'a>>,
+	nature_map: &RegMap<Nature>,
+	options: ParseOptions,
+) -> Result<(Pokemon<'a>, Vec<ParseWarning>), PokemonParseError>
 {
 	use PokemonParseError as Error;
-	let mut lines = data.lines();
+	let mut lines = data.lines().map(str::trim).filter(|it| !it.is_empty());
+	let mut warnings = Vec::new();
 
-	let (species_name, nickname) = find_nickname_and_species(
+	let (first_line, item) = split_item(
 		lines
 			.next()
 			.ok_or_else(|| Error(String::from("missing first line!")))?,
-	)?;
+	);
+	if let Some(item) = item
+	{
+		warnings.push(ParseWarning::Item(String::from(item)));
+	}
+
+	let (first_line, gender) = split_gender(first_line);
+	if let Some(gender) = gender
+	{
+		warnings.push(ParseWarning::Gender(gender));
+	}
+
+	let (species_name, nickname) = find_nickname_and_species(first_line)?;
 	let species_name: Box<str> = pokemon_id_from(species_name).into();
 
 	let species = species_map
 		.get(&species_name)
 		.ok_or_else(|| Error(format!("could not find species '{species_name}'")))?;
 	let mut pokemon = Pokemon::new(species).set_nickname(nickname);
+	let mut has_effort_levels = false;
+	let mut evs = None;
 
 	// TODO: this is fucking awful please do something about this i beg you -morgan 2023-12-11
-	for line in lines.map(str::to_lowercase)
+	for (original, line) in lines.map(|it| (it, it.to_lowercase()))
 	{
 		if let Some(rest) = substring_after_start(&line, "level: ")
 		{
@@ -44,7 +77,17 @@ pub fn deserialize_pokemon<'a>(
 		}
 		else if let Some(rest) = substring_after_start(&line, "shiny: ")
 		{
-			pokemon = pokemon.set_shiny(rest == "yes");
+			let is_shiny = yes_no_line(rest, original, &mut warnings).unwrap_or(pokemon.is_shiny);
+			pokemon = pokemon.set_shiny(is_shiny);
+		}
+		else if let Some(rest) = substring_after_start(&line, "alpha: ")
+		{
+			let is_alpha = yes_no_line(rest, original, &mut warnings).unwrap_or(pokemon.is_alpha);
+			pokemon = pokemon.set_alpha(is_alpha);
+		}
+		else if let Some(rest) = substring_after_start(&line, "alpha move: ")
+		{
+			pokemon = pokemon.set_alpha_move(Some(find_move(move_map, rest)?));
 		}
 		else if let Some(rest) = substring_before_end(&line, " nature")
 		{
@@ -57,28 +100,220 @@ pub fn deserialize_pokemon<'a>(
 		else if let Some(rest) = substring_after_start(&line, "els: ")
 		{
 			pokemon = pokemon.set_effort_levels(parse_effort_levels(rest)?);
+			has_effort_levels = true;
 		}
 		else if let Some(rest) = substring_after_start(&line, "- ")
 		{
-			pokemon = pokemon.add_move(
-				move_map
-					.get(
-						&rest
-							.to_lowercase()
-							.trim()
-							.replace(' ', "_")
-							.into_boxed_str(),
-					)
-					.ok_or_else(|| Error(format!("could not find move '{rest}'")))?,
+			pokemon = pokemon.add_move(find_move(move_map, rest)?);
+		}
+		else if substring_after_start(&line, "ability: ").is_some()
+		{
+			warnings.push(ParseWarning::Ability(value_of(original)));
+		}
+		else if let Some(rest) = substring_after_start(&line, "evs: ")
+		{
+			evs = Some(parse_stat_spread(rest, 0)?);
+		}
+		else if substring_after_start(&line, "ivs: ").is_some()
+		{
+			warnings.push(ParseWarning::Ivs(value_of(original)));
+		}
+		else if substring_after_start(&line, "tera type: ").is_some()
+		{
+			warnings.push(ParseWarning::TeraType(value_of(original)));
+		}
+		else
+		{
+			warnings.push(ParseWarning::UnrecognizedLine(String::from(original)));
+		}
+	}
+
+	if let Some(evs) = evs
+	{
+		if options.convert_evs && !has_effort_levels
+		{
+			let effort_levels = evs.map(effort_level_from_evs);
+			warnings.push(ParseWarning::ConvertedEvs(effort_levels.clone()));
+			pokemon = pokemon.set_effort_levels(effort_levels);
+		}
+		else
+		{
+			warnings.push(ParseWarning::Evs(evs));
+		}
+	}
+
+	Ok((pokemon, warnings))
+}
+
+pub fn deserialize_team<'a>(
+	data: &str,
+	species_map: &'a RegMap<Species<'a>>,
+	move_map: &'a RegMap<Move<'a>>,
+	nature_map: &RegMap<Nature>,
+) -> Result<Team<'a>, PokemonParseError>
+{
+	deserialize_team_with(
+		data,
+		species_map,
+		move_map,
+		nature_map,
+		ParseOptions::default(),
+	)
+	.map(|it| it.0)
+}
+
+pub fn deserialize_team_with<'a>(
+	data: &str,
+	species_map: &'a RegMap<Species<'a>>,
+	move_map: &'a RegMap<Move<'a>>,
+	nature_map: &RegMap<Nature>,
+	options: ParseOptions,
+) -> Result<(Team<'a>, Vec<ParseWarning>), PokemonParseError>
+{
+	let mut team = Team::new(String::from("Untitled"));
+	let mut warnings = Vec::new();
+
+	for block in split_blocks(data)
+	{
+		if let Some(header) = block
+			.trim()
+			.strip_prefix("===")
+			.and_then(|it| it.strip_suffix("==="))
+		{
+			// showdown exports prefix the name with the format, ie. "=== [gen9] Name ==="
+			let header = header.trim();
+			team.name = String::from(
+				header
+					.strip_prefix('[')
+					.and_then(|it| it.split_once(']'))
+					.map_or(header, |it| it.1.trim()),
 			);
 		}
+		else
+		{
+			let (pokemon, pokemon_warnings) =
+				deserialize_pokemon_with(&block, species_map, move_map, nature_map, options)?;
+			team = team.add_member(pokemon);
+			warnings.extend(pokemon_warnings);
+		}
 	}
 
-	Ok(pokemon)
+	Ok((team, warnings))
+}
+
+pub fn serialize_pokemon(pokemon: &Pokemon, nature_map: &RegMap<Nature>) -> String
+{
+	let species_name = display_name_from(&pokemon.species.id);
+	let mut lines = vec![
+		pokemon
+			.nickname
+			.as_ref()
+			.map_or_else(|| species_name.clone(), |it| format!("{it} ({species_name})")),
+		format!("Level: {}", pokemon.level),
+	];
+
+	if pokemon.is_shiny
+	{
+		lines.push(String::from("Shiny: Yes"));
+	}
+	if pokemon.is_alpha
+	{
+		lines.push(String::from("Alpha: Yes"));
+	}
+	if let Some(name) = nature_map
+		.iter()
+		.find(|it| *it.1 == pokemon.nature)
+		.map(|it| it.0)
+	{
+		lines.push(format!("{} Nature", display_name_from(name)));
+	}
+	lines.push(format!("ELs: {}", format_stat_spread(&pokemon.effort_levels)));
+	if let Some(alpha_move) = pokemon.alpha_move
+	{
+		lines.push(format!("Alpha Move: {}", display_name_from(&alpha_move.id)));
+	}
+
+	let mut moves = pokemon.moveset.iter().map(|it| &it.id).collect::<Vec<_>>();
+	moves.sort();
+	lines.extend(moves.into_iter().map(|it| format!("- {}", display_name_from(it))));
+
+	lines.join("\n")
+}
+
+pub fn serialize_team(team: &Team, nature_map: &RegMap<Nature>) -> String
+{
+	std::iter::once(format!("=== {} ===", team.name))
+		.chain(
+			team.members
+				.iter()
+				.map(|it| serialize_pokemon(it, nature_map)),
+		)
+		.collect::<Vec<_>>()
+		.join("\n\n")
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions
+{
+	/// Convert mainline EVs into effort levels when no `ELs:` line is present
+	pub convert_evs: bool,
+}
+
+/// Something in a paste that was understood but could not be represented, and so was dropped
+#[derive(Debug, Clone)]
+pub enum ParseWarning
+{
+	Item(String),
+	Ability(String),
+	Gender(char),
+	Evs(StatBlock),
+	ConvertedEvs(StatBlock),
+	Ivs(String),
+	TeraType(String),
+	/// A line that should have been yes or no but wasn't either
+	NotYesOrNo(String),
+	UnrecognizedLine(String),
+}
+impl std::fmt::Display for ParseWarning
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		match self
+		{
+			Self::Item(item) => write!(f, "held items are not supported, ignoring '{item}'"),
+			Self::Ability(ability) =>
+			{
+				write!(f, "abilities are not supported, ignoring '{ability}'")
+			}
+			Self::Gender(gender) => write!(f, "gender is not supported, ignoring '{gender}'"),
+			Self::Evs(_) => write!(
+				f,
+				"EVs are not supported, ignoring them (they can be converted to effort levels)"
+			),
+			Self::ConvertedEvs(els) =>
+			{
+				write!(f, "converted EVs to effort levels: {}", format_stat_spread(els))
+			}
+			Self::Ivs(ivs) => write!(f, "IVs are not supported, ignoring '{ivs}'"),
+			Self::TeraType(typ) =>
+			{
+				write!(f, "terastallization is not supported, ignoring '{typ}'")
+			}
+			Self::NotYesOrNo(line) => write!(f, "expected yes or no, ignoring line '{line}'"),
+			Self::UnrecognizedLine(line) => write!(f, "could not understand line '{line}'"),
+		}
+	}
 }
 
 #[derive(Debug)]
 pub struct PokemonParseError(String);
+impl std::fmt::Display for PokemonParseError
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		write!(f, "{}", self.0)
+	}
+}
 
 fn find_nickname_and_species(string: &str) -> Result<(&str, Option<String>), PokemonParseError>
 {
@@ -104,7 +339,113 @@ fn find_nickname_and_species(string: &str) -> Result<(&str, Option<String>), Pok
 	}
 }
 
-fn pokemon_id_from(string: &str) -> String
+fn find_move<'a>(
+	move_map: &'a RegMap<Move<'a>>,
+	name: &str,
+) -> Result<&'a Move<'a>, PokemonParseError>
+{
+	move_map
+		.get(&move_id_from(name))
+		.ok_or_else(|| PokemonParseError(format!("could not find move '{name}'")))
+}
+
+fn split_item(string: &str) -> (&str, Option<&str>)
+{
+	string
+		.split_once(" @ ")
+		.map_or((string, None), |(rest, item)| (rest.trim(), Some(item.trim())))
+}
+
+fn split_gender(string: &str) -> (&str, Option<char>)
+{
+	["(M)", "(F)"]
+		.into_iter()
+		.find_map(|marker| {
+			substring_before_end(string, marker)
+				.map(|rest| (rest.trim(), marker.chars().nth(1)))
+		})
+		.unwrap_or((string, None))
+}
+
+fn split_blocks(string: &str) -> Vec<String>
+{
+	string
+		.lines()
+		.fold(vec![String::new()], |mut blocks, line| {
+			if line.trim().is_empty()
+			{
+				blocks.push(String::new());
+			}
+			else if let Some(block) = blocks.last_mut()
+			{
+				block.push_str(line);
+				block.push('\n');
+			}
+			blocks
+		})
+		.into_iter()
+		.filter(|it| !it.is_empty())
+		.collect()
+}
+
+pub fn display_name_from(id: &str) -> String
+{
+	let mut capitalize = true;
+	id.chars()
+		.map(|it| {
+			let ch = if it == '_' { ' ' } else { it };
+			let ch = if capitalize { ch.to_ascii_uppercase() } else { ch };
+			capitalize = ch == ' ' || ch == '-';
+			ch
+		})
+		.collect()
+}
+
+pub fn format_stat_spread(block: &StatBlock) -> String
+{
+	format!(
+		"{} HP / {} Atk / {} Def / {} SpA / {} SpD / {} Spe",
+		block.hp, block.atk, block.def, block.spatk, block.spdef, block.spe
+	)
+}
+
+/// Yes or no, in any case
+pub fn parse_yes_no(string: &str) -> Option<bool>
+{
+	if string.eq_ignore_ascii_case("yes")
+	{
+		Some(true)
+	}
+	else if string.eq_ignore_ascii_case("no")
+	{
+		Some(false)
+	}
+	else
+	{
+		None
+	}
+}
+
+/// The yes or no at the end of a line, warning about the line if it's neither
+fn yes_no_line(value: &str, line: &str, warnings: &mut Vec<ParseWarning>) -> Option<bool>
+{
+	let parsed = parse_yes_no(value);
+	if parsed.is_none()
+	{
+		warnings.push(ParseWarning::NotYesOrNo(String::from(line)));
+	}
+	parsed
+}
+
+fn value_of(line: &str) -> String
+{
+	line.split_once(':')
+		.map_or(line, |it| it.1)
+		.trim()
+		.to_owned()
+}
+
+pub fn pokemon_id_from(string: &str) -> String
 {
 	string
 		.trim()
@@ -125,6 +466,15 @@ fn pokemon_id_from(string: &str) -> String
 		.replace("__", "_")
 }
 
+pub fn move_id_from(string: &str) -> Box<str>
+{
+	string
+		.to_lowercase()
+		.trim()
+		.replace(' ', "_")
+		.into_boxed_str()
+}
+
 fn find_last(ch: char, string: &str) -> Option<usize>
 {
 	string
@@ -146,14 +496,19 @@ fn substring_before_end<'a>(string: &'a str, pattern: &str) -> Option<&'a str>
 	(index + pattern.len() == string.len()).then(|| &string[..index])
 }
 
-fn parse_effort_levels(string: &str) -> Result<StatBlock, PokemonParseError>
+pub fn parse_effort_levels(string: &str) -> Result<StatBlock, PokemonParseError>
+{
+	parse_stat_spread(string, 10)
+}
+
+fn parse_stat_spread(string: &str, default: i32) -> Result<StatBlock, PokemonParseError>
 {
 	let mut stat_map = HashMap::new();
 	let blocks = string.split('/').map(str::trim);
 	for block in blocks
 	{
 		let captures = EFFORT_REGEX.captures(block).ok_or_else(|| {
-			PokemonParseError(format!("could not interpret effort level from '{block}'"))
+			PokemonParseError(format!("could not interpret stat value from '{block}'"))
 		})?;
 		let value: i32 = captures["val"].parse().unwrap();
 		let stat: Stat = captures["stat"].parse().unwrap();
@@ -161,6 +516,160 @@ fn parse_effort_levels(string: &str) -> Result<StatBlock, PokemonParseError>
 	}
 
 	Ok(StatBlock::generate(|stat| {
-		stat_map.get(&stat).map_or(10, i32::clone)
+		stat_map.get(&stat).map_or(default, i32::clone)
 	}))
 }
+
+fn effort_level_from_evs(evs: i32) -> i32
+{
+	// 252 evs is a fully invested stat, so scale that onto the 0-10 effort level range
+	(f64::from(evs.clamp(0, 252)) * 10.0 / 252.0).round() as i32
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{MOVE_MAP, NATURE_MAP, SPECIES_MAP};
+
+	const MAINLINE_PASTE: &str = "Spiky (Garchomp) (M) @ Choice Scarf
+Ability: Rough Skin
+Tera Type: Dragon
+EVs: 252 Atk / 4 SpD / 126 Spe
+IVs: 0 SpA
+Jolly Nature
+- Dragon Claw
+- Stone Edge";
+
+	fn parse(data: &str, convert_evs: bool) -> (Pokemon<'static>, Vec<ParseWarning>)
+	{
+		deserialize_pokemon_with(
+			data,
+			&SPECIES_MAP,
+			&MOVE_MAP,
+			&NATURE_MAP,
+			ParseOptions { convert_evs },
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn warns_about_held_items()
+	{
+		let (pokemon, warnings) = parse(MAINLINE_PASTE, false);
+
+		assert_eq!(pokemon.nickname.as_deref(), Some("Spiky"));
+		assert_eq!(&*pokemon.species.id, "garchomp");
+		assert!(warnings
+			.iter()
+			.any(|it| matches!(it, ParseWarning::Item(item) if item == "Choice Scarf")));
+		assert!(warnings
+			.iter()
+			.any(|it| matches!(it, ParseWarning::Gender('M'))));
+	}
+
+	#[test]
+	fn warns_about_abilities()
+	{
+		let (_, warnings) = parse(MAINLINE_PASTE, false);
+
+		assert!(warnings
+			.iter()
+			.any(|it| matches!(it, ParseWarning::Ability(ability) if ability == "Rough Skin")));
+	}
+
+	#[test]
+	fn warns_about_tera_types()
+	{
+		let (_, warnings) = parse(MAINLINE_PASTE, false);
+
+		assert!(warnings
+			.iter()
+			.any(|it| matches!(it, ParseWarning::TeraType(typ) if typ == "Dragon")));
+		assert!(warnings
+			.iter()
+			.any(|it| matches!(it, ParseWarning::Ivs(ivs) if ivs == "0 SpA")));
+	}
+
+	#[test]
+	fn ignores_evs_unless_converting()
+	{
+		let (pokemon, warnings) = parse(MAINLINE_PASTE, false);
+
+		assert_eq!(pokemon.effort_levels.atk, 10);
+		assert_eq!(pokemon.effort_levels.spatk, 10);
+		assert!(warnings.iter().any(|it| matches!(it, ParseWarning::Evs(_))));
+		assert!(!warnings
+			.iter()
+			.any(|it| matches!(it, ParseWarning::ConvertedEvs(_))));
+	}
+
+	#[test]
+	fn converts_evs_to_effort_levels()
+	{
+		let (pokemon, warnings) = parse(MAINLINE_PASTE, true);
+
+		assert_eq!(pokemon.effort_levels.atk, 10);
+		assert_eq!(pokemon.effort_levels.spdef, 0);
+		assert_eq!(pokemon.effort_levels.spe, 5);
+		assert_eq!(pokemon.effort_levels.hp, 0);
+		assert!(warnings
+			.iter()
+			.any(|it| matches!(it, ParseWarning::ConvertedEvs(_))));
+	}
+
+	#[test]
+	fn keeps_effort_levels_over_evs()
+	{
+		let data = format!("{MAINLINE_PASTE}\nELs: 3 HP");
+		let (pokemon, warnings) = parse(&data, true);
+
+		assert_eq!(pokemon.effort_levels.hp, 3);
+		assert_eq!(pokemon.effort_levels.atk, 10);
+		assert_eq!(pokemon.effort_levels.spe, 10);
+		assert!(warnings.iter().any(|it| matches!(it, ParseWarning::Evs(_))));
+	}
+
+	#[test]
+	fn collects_warnings_from_every_member()
+	{
+		let data =
+			format!("=== [gen9] Mainline ===\n\n{MAINLINE_PASTE}\n\n{MAINLINE_PASTE}\nWeird line");
+		let (team, warnings) = deserialize_team_with(
+			&data,
+			&SPECIES_MAP,
+			&MOVE_MAP,
+			&NATURE_MAP,
+			ParseOptions::default(),
+		)
+		.unwrap();
+
+		assert_eq!(team.name, "Mainline");
+		assert_eq!(team.members.len(), 2);
+		assert_eq!(
+			warnings
+				.iter()
+				.filter(|it| matches!(it, ParseWarning::Item(_)))
+				.count(),
+			2
+		);
+		assert!(warnings
+			.iter()
+			.any(|it| matches!(it, ParseWarning::UnrecognizedLine(line) if line == "Weird line")));
+	}
+
+	#[test]
+	fn reads_yes_or_no_in_any_case()
+	{
+		let (pokemon, warnings) = parse("Garchomp\nShiny: YES\nAlpha: Yes", false);
+		assert!(pokemon.is_shiny && pokemon.is_alpha);
+		assert!(warnings.is_empty());
+
+		let (pokemon, warnings) = parse("Garchomp\nShiny: true\nAlpha: no", false);
+		assert!(!pokemon.is_shiny && !pokemon.is_alpha);
+		assert!(matches!(
+			&warnings[..],
+			[ParseWarning::NotYesOrNo(line)] if line == "Shiny: true"
+		));
+	}
+}
@@ -0,0 +1,405 @@
+use crate::{data, parsing};
+
+use data::{Move, Nature, Pokemon, RegMap, Ruleset, Species, Style, Team};
+use std::io::{self, BufRead, Write};
+
+const MAIN_HELP: &str = "commands:
+	add <species>        add a new member and start editing it
+	edit <n>             edit member n
+	remove <n>           remove member n
+	name <team name>     rename the team
+	show                 print the team in paste format
+	save <file>          write the team to a file in paste format
+	quit                 stop building";
+
+const EDIT_HELP: &str = "commands:
+	species <name>       change species
+	nick [name]          set or clear the nickname
+	level <n>            set the level
+	nature <name>        set the nature
+	els <spread>         set effort levels, ie. '10 atk / 7 spe'
+	shiny <yes|no>       set whether it is shiny
+	alpha <yes|no>       set whether it is an alpha, taking away any alpha move if not
+	alphamove [move]     set or clear the alpha move, which does not count towards the four
+	moves [filter]       list moves, optionally only those whose name or type contains filter
+	learn <move>         add a move
+	forget <move>        remove a move
+	done                 go back to the team";
+
+/// A prompt driven teambuilder, reading commands line by line from `input`
+pub struct TeamBuilder<'a, R, W>
+where
+	R: BufRead,
+	W: Write,
+{
+	input: R,
+	output: W,
+	species_map: &'a RegMap<Species<'a>>,
+	move_map: &'a RegMap<Move<'a>>,
+	nature_map: &'a RegMap<Nature>,
+	rules: &'a Ruleset,
+	team: Team<'a>,
+}
+impl<'a, R, W> TeamBuilder<'a, R, W>
+where
+	R: BufRead,
+	W: Write,
+{
+	pub fn new(
+		input: R,
+		output: W,
+		species_map: &'a RegMap<Species<'a>>,
+		move_map: &'a RegMap<Move<'a>>,
+		nature_map: &'a RegMap<Nature>,
+		rules: &'a Ruleset,
+	) -> Self
+	{
+		Self {
+			input,
+			output,
+			species_map,
+			move_map,
+			nature_map,
+			rules,
+			team: Team::new(String::from("Untitled")),
+		}
+	}
+
+	pub fn with_team(mut self, team: Team<'a>) -> Self
+	{
+		self.team = team;
+		self
+	}
+
+	/// Runs until the user quits or input runs out, handing back the team as it was left
+	pub fn run(mut self) -> io::Result<Team<'a>>
+	{
+		writeln!(self.output, "{MAIN_HELP}")?;
+		loop
+		{
+			self.print_team()?;
+			let Some(line) = self.prompt("team")?
+			else
+			{
+				break;
+			};
+			let (command, rest) = split_command(&line);
+
+			match command
+			{
+				"add" if self.team.members.len() >= data::MAX_TEAM_SIZE =>
+				{
+					writeln!(
+						self.output,
+						"the team already has {} members, remove one first",
+						data::MAX_TEAM_SIZE
+					)?;
+				}
+				"add" => match self.find_species(rest)
+				{
+					Some(species) =>
+					{
+						self.team.members.push(Pokemon::new(species));
+						let index = self.team.members.len() - 1;
+						self.edit(index)?;
+					}
+					None => writeln!(self.output, "no species called '{rest}'")?,
+				},
+				"edit" => match self.member_index(rest)
+				{
+					Some(index) => self.edit(index)?,
+					None => writeln!(self.output, "no member number '{rest}'")?,
+				},
+				"remove" => match self.member_index(rest)
+				{
+					Some(index) =>
+					{
+						self.team.members.remove(index);
+					}
+					None => writeln!(self.output, "no member number '{rest}'")?,
+				},
+				"name" if !rest.is_empty() => self.team.name = String::from(rest),
+				"show" => writeln!(
+					self.output,
+					"{}",
+					parsing::serialize_team(&self.team, self.nature_map)
+				)?,
+				"save" if !rest.is_empty() =>
+				{
+					match std::fs::write(rest, parsing::serialize_team(&self.team, self.nature_map))
+					{
+						Ok(()) => writeln!(self.output, "saved to {rest}")?,
+						Err(err) => writeln!(self.output, "could not save to {rest}: {err}")?,
+					}
+				}
+				"quit" | "exit" => break,
+				_ => writeln!(self.output, "{MAIN_HELP}")?,
+			}
+		}
+
+		Ok(self.team)
+	}
+
+	fn edit(&mut self, index: usize) -> io::Result<()>
+	{
+		writeln!(self.output, "{EDIT_HELP}")?;
+		loop
+		{
+			self.print_preview(index)?;
+			let context = String::from(self.team.members[index].name());
+			let Some(line) = self.prompt(&context)?
+			else
+			{
+				return Ok(());
+			};
+			let (command, rest) = split_command(&line);
+			let pokemon = &mut self.team.members[index];
+
+			match command
+			{
+				"species" => match self.species_map.get(&*parsing::pokemon_id_from(rest))
+				{
+					Some(species) => pokemon.species = species,
+					None => writeln!(self.output, "no species called '{rest}'")?,
+				},
+				"nick" => pokemon.nickname = (!rest.is_empty()).then(|| String::from(rest)),
+				"level" => match rest.parse::<u8>()
+				{
+					Ok(level @ 1..=100) => pokemon.level = level,
+					_ => writeln!(self.output, "level must be between 1 and 100")?,
+				},
+				"nature" => match self.nature_map.get(rest.to_lowercase().as_str())
+				{
+					Some(nature) => pokemon.nature = *nature,
+					None => writeln!(self.output, "no nature called '{rest}'")?,
+				},
+				"els" => match parsing::parse_effort_levels(&rest.to_lowercase())
+				{
+					Ok(els) if els.iter().all(|it| (0..=10).contains(&it.1)) =>
+					{
+						pokemon.effort_levels = els;
+						pokemon.apply_alpha_effort_levels();
+					}
+					Ok(_) => writeln!(self.output, "effort levels must be between 0 and 10")?,
+					Err(err) => writeln!(self.output, "{err}")?,
+				},
+				"shiny" => match parsing::parse_yes_no(rest)
+				{
+					Some(is_shiny) => pokemon.is_shiny = is_shiny,
+					None => writeln!(self.output, "expected yes or no, got '{rest}'")?,
+				},
+				"alpha" => match parsing::parse_yes_no(rest)
+				{
+					Some(is_alpha) =>
+					{
+						pokemon.is_alpha = is_alpha;
+						pokemon.apply_alpha_effort_levels();
+						// only alphas can have an alpha move
+						if !is_alpha
+						{
+							pokemon.alpha_move = None;
+						}
+					}
+					None => writeln!(self.output, "expected yes or no, got '{rest}'")?,
+				},
+				"alphamove" if rest.is_empty() => pokemon.alpha_move = None,
+				"alphamove" => match self.move_map.get(&parsing::move_id_from(rest))
+				{
+					Some(_) if !pokemon.is_alpha =>
+					{
+						writeln!(self.output, "only alphas can have an alpha move")?;
+					}
+					Some(mv) => pokemon.alpha_move = Some(mv),
+					None => writeln!(self.output, "no move called '{rest}'")?,
+				},
+				"moves" => self.print_moves(rest)?,
+				"learn" => match self.move_map.get(&parsing::move_id_from(rest))
+				{
+					Some(_) if pokemon.moveset.len() >= data::MAX_MOVES =>
+					{
+						writeln!(self.output, "already knows four moves, forget one first")?;
+					}
+					Some(mv) =>
+					{
+						pokemon.moveset.insert(mv);
+					}
+					None => writeln!(self.output, "no move called '{rest}'")?,
+				},
+				"forget" =>
+				{
+					let id = parsing::move_id_from(rest);
+					pokemon.moveset.retain(|it| it.id != id);
+				}
+				"done" | "back" => return Ok(()),
+				_ => writeln!(self.output, "{EDIT_HELP}")?,
+			}
+		}
+	}
+
+	fn prompt(&mut self, context: &str) -> io::Result<Option<String>>
+	{
+		write!(self.output, "{context}> ")?;
+		self.output.flush()?;
+
+		let mut line = String::new();
+		Ok((self.input.read_line(&mut line)? != 0).then(|| String::from(line.trim())))
+	}
+
+	fn print_team(&mut self) -> io::Result<()>
+	{
+		writeln!(
+			self.output,
+			"\n{} ({} members)",
+			self.team.name,
+			self.team.members.len()
+		)?;
+		for (i, pokemon) in self.team.members.iter().enumerate()
+		{
+			writeln!(
+				self.output,
+				"  {}. {} (Lv. {})",
+				i + 1,
+				pokemon.name(),
+				pokemon.level
+			)?;
+		}
+
+		Ok(())
+	}
+
+	fn print_preview(&mut self, index: usize) -> io::Result<()>
+	{
+		let pokemon = &self.team.members[index];
+		writeln!(
+			self.output,
+			"\n{}",
+			parsing::serialize_pokemon(pokemon, self.nature_map)
+		)?;
+		writeln!(
+			self.output,
+			"Stats: {}",
+			parsing::format_stat_spread(&pokemon.stats())
+		)?;
+		writeln!(
+			self.output,
+			"Action time: {}",
+			pokemon.base_action_time(self.rules)
+		)
+	}
+
+	fn print_moves(&mut self, filter: &str) -> io::Result<()>
+	{
+		let filter = filter.to_lowercase();
+		let mut moves = self
+			.move_map
+			.values()
+			.filter(|it| it.id.contains(&filter) || it.move_type.id.contains(&filter))
+			.collect::<Vec<_>>();
+		moves.sort_by(|a, b| a.id.cmp(&b.id));
+
+		writeln!(
+			self.output,
+			"{:<20} {:<9} {:<9} {:>13} {:>13}",
+			"move", "type", "category", "power R/A/S", "acc R/A/S"
+		)?;
+		for mv in moves
+		{
+			let triad = |it: &data::StyleTriad<i32>| {
+				format!(
+					"{}/{}/{}",
+					it[Style::Regular],
+					it[Style::Agile],
+					it[Style::Strong]
+				)
+			};
+			writeln!(
+				self.output,
+				"{:<20} {:<9} {:<9} {:>13} {:>13}",
+				parsing::display_name_from(&mv.id),
+				mv.move_type.id,
+				format!("{:?}", mv.category).to_lowercase(),
+				triad(&mv.power),
+				triad(&mv.accuracy)
+			)?;
+		}
+
+		Ok(())
+	}
+
+	fn find_species(&self, name: &str) -> Option<&'a Species<'a>>
+	{
+		self.species_map.get(&*parsing::pokemon_id_from(name))
+	}
+
+	fn member_index(&self, number: &str) -> Option<usize>
+	{
+		number
+			.parse::<usize>()
+			.ok()
+			.filter(|it| (1..=self.team.members.len()).contains(it))
+			.map(|it| it - 1)
+	}
+}
+
+fn split_command(line: &str) -> (&str, &str)
+{
+	line.split_once(' ')
+		.map_or((line, ""), |(command, rest)| (command, rest.trim()))
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use crate::{MOVE_MAP, NATURE_MAP, RULESET, SPECIES_MAP};
+
+	/// Runs the builder over `commands`, one per line, returning the team and everything printed
+	fn build(commands: &str) -> (Team<'static>, String)
+	{
+		let mut output = Vec::new();
+		let team = TeamBuilder::new(
+			commands.as_bytes(),
+			&mut output,
+			&SPECIES_MAP,
+			&MOVE_MAP,
+			&NATURE_MAP,
+			&RULESET,
+		)
+		.run()
+		.unwrap();
+
+		(team, String::from_utf8(output).unwrap())
+	}
+
+	#[test]
+	fn clears_the_alpha_move_with_alpha()
+	{
+		let (team, _) = build("add garchomp\nalpha yes\nalphamove stone edge\nalpha no\ndone\n");
+
+		let pokemon = &team.members[0];
+		assert!(!pokemon.is_alpha);
+		assert_eq!(pokemon.alpha_move, None);
+	}
+
+	#[test]
+	fn rejects_anything_but_yes_or_no()
+	{
+		let (team, output) = build("add garchomp\nshiny yes\nalpha yes\nshiny maybe\nalpha y\n");
+
+		let pokemon = &team.members[0];
+		assert!(pokemon.is_shiny);
+		assert!(pokemon.is_alpha);
+		assert!(output.contains("expected yes or no, got 'maybe'"));
+		assert!(output.contains("expected yes or no, got 'y'"));
+	}
+
+	#[test]
+	fn stops_adding_at_a_full_team()
+	{
+		let commands = "add garchomp\ndone\n".repeat(data::MAX_TEAM_SIZE + 1);
+		let (team, output) = build(&commands);
+
+		assert_eq!(team.members.len(), data::MAX_TEAM_SIZE);
+		assert!(output.contains("the team already has 6 members"));
+	}
+}